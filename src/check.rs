@@ -0,0 +1,941 @@
+use crate::config::{apply_preset, Config, Theme};
+use crate::window::template::Template;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{fmt, fs, ops::Range, path::Path};
+use toml::Table;
+use toml_edit::{ImDocument, Item, TableLike, Value};
+use unicode_script::Script;
+
+/// Severity of a diagnostic.
+#[derive(Clone, Copy, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Problem found in a configuration file.
+///
+/// Note that the line and the column start at 1, and are 0 when unknown.
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "{}:{}: ", self.line, self.column)?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)?;
+
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\n  = help: {suggestion}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Expected value of a configuration key.
+#[derive(Clone, Copy)]
+enum Kind {
+    /// Only checked by the deserialization.
+    Any,
+    /// Key without default value.
+    Required(&'static Kind),
+    Boolean,
+    String,
+    Strings,
+    /// Integer greater than or equal to zero.
+    Unsigned,
+    PositiveInteger,
+    PositiveNumber,
+    /// One of the given values.
+    Choice(&'static [&'static str]),
+    Color,
+    FontWeight,
+    Template,
+    Preset,
+    /// Table with ISO 15924 script codes as keys.
+    Scripts,
+    Table(&'static [(&'static str, Kind)]),
+}
+
+impl Kind {
+    /// Returns whether the type of a value is the expected one.
+    fn accepts(&self, item: &Item) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Required(kind) => kind.accepts(item),
+            Self::Boolean => item.is_bool(),
+            Self::Unsigned | Self::PositiveInteger => item.is_integer(),
+            Self::PositiveNumber => item.is_integer() || item.is_float(),
+            Self::Strings => item
+                .as_array()
+                .is_some_and(|array| array.iter().all(|value| value.is_str())),
+            Self::Scripts | Self::Table(_) => item.is_table_like(),
+            Self::String
+            | Self::Choice(_)
+            | Self::Color
+            | Self::FontWeight
+            | Self::Template
+            | Self::Preset => item.is_str(),
+        }
+    }
+
+    /// Returns the description of the expected type.
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Any => "any value",
+            Self::Required(kind) => kind.description(),
+            Self::Boolean => "a boolean",
+            Self::Unsigned | Self::PositiveInteger => "an integer",
+            Self::PositiveNumber => "a number",
+            Self::Strings => "an array of strings",
+            Self::Scripts | Self::Table(_) => "a table",
+            Self::String
+            | Self::Choice(_)
+            | Self::Color
+            | Self::FontWeight
+            | Self::Template
+            | Self::Preset => "a string",
+        }
+    }
+}
+
+const FONT: &[(&str, Kind)] = &[
+    ("family", Kind::Required(&Kind::String)),
+    ("fallbacks", Kind::Strings),
+    ("size", Kind::Required(&Kind::Unsigned)),
+    ("weight", Kind::Required(&Kind::FontWeight)),
+    ("slant", Kind::Choice(&["roman", "italic"])),
+    ("underline", Kind::Boolean),
+    ("overstrike", Kind::Boolean),
+];
+
+const SECTION_THEME: &[(&str, Kind)] = &[
+    ("background", Kind::Required(&Kind::Color)),
+    ("foreground", Kind::Required(&Kind::Color)),
+    ("font", Kind::Required(&Kind::Table(FONT))),
+];
+
+const HINT_THEME: &[(&str, Kind)] = &[("foreground", Kind::Color), ("font", Kind::Table(FONT))];
+
+const WIDGET_THEME: &[(&str, Kind)] = &[
+    ("background", Kind::Color),
+    ("foreground", Kind::Color),
+    ("font", Kind::Table(FONT)),
+];
+
+const TOOLKIT_THEME: &[(&str, Kind)] = &[
+    ("frame", Kind::Table(WIDGET_THEME)),
+    ("label", Kind::Table(WIDGET_THEME)),
+    ("button", Kind::Table(WIDGET_THEME)),
+    ("tab", Kind::Table(WIDGET_THEME)),
+    ("idle", Kind::Table(WIDGET_THEME)),
+    ("running", Kind::Table(WIDGET_THEME)),
+];
+
+const THEME: &[(&str, Kind)] = &[
+    ("preset", Kind::Preset),
+    ("header", Kind::Required(&Kind::Table(SECTION_THEME))),
+    ("body", Kind::Required(&Kind::Table(SECTION_THEME))),
+    ("selected", Kind::Table(SECTION_THEME)),
+    (
+        "anchor",
+        Kind::Choice(&["below-right", "below-left", "above-right", "above-left"]),
+    ),
+    ("offset", Kind::Unsigned),
+    ("code", Kind::Table(HINT_THEME)),
+    ("remaining_code", Kind::Table(HINT_THEME)),
+    ("text", Kind::Table(HINT_THEME)),
+    ("can_commit", Kind::Table(HINT_THEME)),
+    ("toolkit", Kind::Table(TOOLKIT_THEME)),
+    ("scripts", Kind::Scripts),
+];
+
+const DISPLAY: &[(&str, Kind)] = &[
+    ("template", Kind::Template),
+    ("commit_glyph", Kind::String),
+    ("auto_hide", Kind::Boolean),
+    ("show_delay", Kind::Unsigned),
+    ("placement", Kind::Choice(&["cursor", "docked"])),
+    (
+        "dock",
+        Kind::Choice(&["top-left", "top-right", "bottom-left", "bottom-right"]),
+    ),
+    ("layout", Kind::Choice(&["vertical", "horizontal"])),
+    ("separator", Kind::String),
+    ("max_width", Kind::Unsigned),
+    ("max_text_length", Kind::Unsigned),
+    ("scaling", Kind::PositiveNumber),
+    ("direction", Kind::Choice(&["auto", "ltr", "rtl"])),
+];
+
+/// Shared with afrim.
+const CORE: &[(&str, Kind)] = &[
+    ("buffer_size", Kind::Required(&Kind::PositiveInteger)),
+    ("auto_capitalize", Kind::Boolean),
+    ("page_size", Kind::Unsigned),
+    ("auto_commit", Kind::Required(&Kind::Boolean)),
+];
+
+const INFO: &[(&str, Kind)] = &[
+    ("name", Kind::Required(&Kind::String)),
+    ("description", Kind::Required(&Kind::String)),
+    ("authors", Kind::Required(&Kind::Strings)),
+    ("website", Kind::Required(&Kind::String)),
+    ("version", Kind::Required(&Kind::String)),
+];
+
+const DICTIONARY: &[(&str, Kind)] = &[("path", Kind::String)];
+
+const RANKING: &[(&str, Kind)] = &[("learn", Kind::Boolean), ("path", Kind::String)];
+
+const SHORTCODES: &[(&str, Kind)] = &[("enabled", Kind::Boolean)];
+
+/// The afrim sections are only checked by afrim.
+const CONFIG: &[(&str, Kind)] = &[
+    ("theme", Kind::Table(THEME)),
+    ("display", Kind::Table(DISPLAY)),
+    ("core", Kind::Table(CORE)),
+    ("info", Kind::Table(INFO)),
+    ("dictionary", Kind::Table(DICTIONARY)),
+    ("ranking", Kind::Table(RANKING)),
+    ("shortcodes", Kind::Table(SHORTCODES)),
+    ("data", Kind::Any),
+    ("translators", Kind::Any),
+    ("translation", Kind::Any),
+];
+
+const FONT_WEIGHTS: &[&str] = &["normal", "bold"];
+
+/// Colors named by Tk, without their spaces and their numbered variants.
+const COLOR_NAMES: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrod",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslateblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "navyblue",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "turquoise",
+    "violet",
+    "violetred",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
+
+/// Checks the configuration file and returns the problems found.
+pub fn check(filepath: &Path) -> Result<Vec<Diagnostic>> {
+    let content =
+        fs::read_to_string(filepath).with_context(|| format!("Couldn't open file {filepath:?}"))?;
+
+    Ok(Checker::new(&content).check())
+}
+
+/// Checks a configuration file merged with other files.
+///
+/// Unlike [`check`], a missing key is not a problem since it can be defined
+/// in another file.
+pub fn check_layer(filepath: &Path) -> Result<Vec<Diagnostic>> {
+    let content =
+        fs::read_to_string(filepath).with_context(|| format!("Couldn't open file {filepath:?}"))?;
+
+    Ok(Checker::new(&content).partial().check())
+}
+
+struct Checker<'a> {
+    content: &'a str,
+    partial: bool,
+    /// Whether the theme uses a preset, which may define its missing keys.
+    preset: bool,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(content: &'a str) -> Self {
+        Self {
+            content,
+            partial: false,
+            preset: false,
+            diagnostics: vec![],
+        }
+    }
+
+    fn partial(mut self) -> Self {
+        self.partial = true;
+
+        self
+    }
+
+    fn check(mut self) -> Vec<Diagnostic> {
+        let document = match ImDocument::parse(self.content) {
+            Ok(document) => document,
+            Err(err) => {
+                self.report(Severity::Error, err.span(), err.message(), None);
+
+                return self.diagnostics;
+            }
+        };
+
+        self.check_table(document.as_table(), CONFIG, "", None);
+
+        // The keys of a theme can be defined by its preset.
+        self.preset = document
+            .get("theme")
+            .is_some_and(|theme| theme.get("preset").is_some());
+        if !self.partial {
+            self.check_required(document.as_table(), CONFIG, "", None);
+
+            if self.preset {
+                self.check_preset();
+            }
+        }
+
+        // The deserialization catches the problems unknown by the schema.
+        let errors = self
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error);
+        match toml::from_str::<Config>(self.content) {
+            Err(_) if errors => (),
+            Err(err) if err.message().starts_with("missing field") => (),
+            Err(err) => self.report(Severity::Error, err.span(), err.message(), None),
+            Ok(_) => (),
+        }
+
+        // The problems are reported from the top of the file.
+        self.diagnostics
+            .sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+
+        self.diagnostics
+    }
+
+    /// Reports the missing keys of the theme, once its preset is applied.
+    ///
+    /// Note that these problems are not located.
+    fn check_preset(&mut self) {
+        let Ok(mut table) = toml::from_str::<Table>(self.content) else {
+            return;
+        };
+
+        // An unknown preset is reported with the keys.
+        if apply_preset(&mut table).is_err() {
+            return;
+        }
+        table.retain(|name, _| name == "theme");
+        let Ok(content) = toml::to_string(&table) else {
+            return;
+        };
+        let Ok(document) = ImDocument::parse(content.as_str()) else {
+            return;
+        };
+
+        let mut checker = Checker::new(&content);
+        checker.check_required(document.as_table(), &CONFIG[..1], "", None);
+        self.diagnostics.extend(
+            checker
+                .diagnostics
+                .into_iter()
+                .map(|diagnostic| Diagnostic {
+                    line: 0,
+                    column: 0,
+                    ..diagnostic
+                }),
+        );
+    }
+
+    fn report(
+        &mut self,
+        severity: Severity,
+        span: Option<Range<usize>>,
+        message: &str,
+        suggestion: Option<String>,
+    ) {
+        let (line, column) = span
+            .map(|span| position(self.content, span.start))
+            .unwrap_or_default();
+
+        self.diagnostics.push(Diagnostic {
+            severity,
+            line,
+            column,
+            message: message.to_owned(),
+            suggestion,
+        });
+    }
+
+    fn check_table(
+        &mut self,
+        table: &dyn TableLike,
+        schema: &[(&str, Kind)],
+        path: &str,
+        span: Option<Range<usize>>,
+    ) {
+        for (name, item) in table.iter() {
+            let key_span = table
+                .get_key_value(name)
+                .and_then(|(key, _)| key.span())
+                .or(span.to_owned());
+            let key_path = if path.is_empty() {
+                name.to_owned()
+            } else {
+                format!("{path}.{name}")
+            };
+
+            match schema.iter().find(|(expected, _)| *expected == name) {
+                Some((_, kind)) => self.check_item(item, *kind, &key_path, key_span),
+                None => {
+                    let suggestion = schema
+                        .iter()
+                        .map(|(expected, _)| (distance(name, expected), expected))
+                        .filter(|(distance, _)| *distance <= 3)
+                        .min()
+                        .map(|(_, expected)| format!("did you mean `{expected}`?"));
+                    let (severity, message) = if path.is_empty() {
+                        (Severity::Warning, format!("unknown section `{key_path}`"))
+                    } else {
+                        (Severity::Error, format!("unknown key `{key_path}`"))
+                    };
+
+                    self.report(severity, key_span, &message, suggestion);
+                }
+            }
+        }
+    }
+
+    /// Reports the required keys missing in a table and in its tables.
+    fn check_required(
+        &mut self,
+        table: &dyn TableLike,
+        schema: &[(&str, Kind)],
+        path: &str,
+        span: Option<Range<usize>>,
+    ) {
+        for (name, kind) in schema {
+            let key_path = if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{path}.{name}")
+            };
+
+            match (table.get_key_value(name), kind) {
+                // The missing keys of the theme are checked with its preset.
+                (Some(_), _) if self.preset && key_path == "theme" => (),
+                (Some((key, item)), Kind::Table(schema) | Kind::Required(Kind::Table(schema))) => {
+                    if let Some(table) = item.as_table_like() {
+                        let span = key.span().or(span.to_owned());
+                        self.check_required(table, schema, &key_path, span);
+                    }
+                }
+                (None, Kind::Required(_)) => {
+                    self.report(
+                        Severity::Error,
+                        span.to_owned(),
+                        &format!("missing key `{key_path}`"),
+                        None,
+                    );
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn check_item(&mut self, item: &Item, kind: Kind, path: &str, span: Option<Range<usize>>) {
+        let span = item.span().or(span);
+
+        if !kind.accepts(item) {
+            self.report(
+                Severity::Error,
+                span,
+                &format!(
+                    "`{path}` should be {}, found {}",
+                    kind.description(),
+                    type_name(item)
+                ),
+                None,
+            );
+
+            return;
+        }
+
+        match kind {
+            Kind::Any | Kind::Boolean | Kind::String | Kind::Strings => (),
+            Kind::Required(kind) => self.check_item(item, *kind, path, span),
+            Kind::Unsigned => match item.as_integer() {
+                Some(value) if value < 0 => self.report(
+                    Severity::Error,
+                    span,
+                    &format!("`{path}` should be positive or zero, found {value}"),
+                    None,
+                ),
+                _ => (),
+            },
+            Kind::PositiveNumber => match item
+                .as_float()
+                .or(item.as_integer().map(|value| value as f64))
+            {
+                Some(value) if !value.is_finite() || value <= 0.0 => self.report(
+                    Severity::Error,
+                    span,
+                    &format!("`{path}` should be positive, found {value}"),
+                    None,
+                ),
+                _ => (),
+            },
+            Kind::Choice(values) => match item.as_str() {
+                Some(value) if !values.contains(&value) => self.report(
+                    Severity::Error,
+                    span,
+                    &format!("unknown value `{value}` for `{path}`"),
+                    Some(format!("expected one of {values:?}")),
+                ),
+                _ => (),
+            },
+            Kind::Table(schema) => {
+                if let Some(table) = item.as_table_like() {
+                    self.check_table(table, schema, path, span);
+                }
+            }
+            Kind::Scripts => {
+                let Some(table) = item.as_table_like() else {
+                    return;
+                };
+
+                for (name, item) in table.iter() {
+                    let key_span = table
+                        .get_key_value(name)
+                        .and_then(|(key, _)| key.span())
+                        .or(span.to_owned());
+                    let key_path = format!("{path}.{name}");
+
+                    if Script::from_short_name(name).is_some() {
+                        self.check_item(item, Kind::String, &key_path, key_span);
+                    } else {
+                        self.report(
+                            Severity::Error,
+                            key_span,
+                            &format!("unknown script `{key_path}`"),
+                            Some("use an ISO 15924 code like \"Nkoo\"".to_owned()),
+                        );
+                    }
+                }
+            }
+            Kind::Color => match item.as_str().map(|color| (color, is_color(color))) {
+                Some((color, None)) => self.report(
+                    Severity::Error,
+                    span,
+                    &format!("invalid color `{color}` for `{path}`"),
+                    Some(
+                        "use an hexadecimal color like \"#1e1e1e\" or a color name like \"white\""
+                            .to_owned(),
+                    ),
+                ),
+                Some((color, Some(false))) => self.report(
+                    Severity::Warning,
+                    span,
+                    &format!("unknown color name `{color}` for `{path}`"),
+                    Some(
+                        "use an hexadecimal color like \"#1e1e1e\" or a color name of Tk"
+                            .to_owned(),
+                    ),
+                ),
+                _ => (),
+            },
+            Kind::FontWeight => match item.as_str() {
+                Some(weight) if !FONT_WEIGHTS.contains(&weight) => self.report(
+                    Severity::Error,
+                    span,
+                    &format!("unknown font weight `{weight}` for `{path}`"),
+                    Some(format!("expected one of {FONT_WEIGHTS:?}")),
+                ),
+                _ => (),
+            },
+            Kind::Template => {
+                if let Some(template) = item.as_str() {
+                    Template::unknown_placeholders(template)
+                        .iter()
+                        .for_each(|placeholder| {
+                            self.report(
+                                Severity::Warning,
+                                span.to_owned(),
+                                &format!("unknown placeholder `{{{placeholder}}}` in `{path}`"),
+                                Some("expected one of {index}, {text}, {code}, {remaining_code} or {can_commit}".to_owned()),
+                            )
+                        });
+                }
+            }
+            Kind::Preset => match item.as_str() {
+                Some(preset) if !Theme::presets().contains(&preset) => self.report(
+                    Severity::Error,
+                    span,
+                    &format!("unknown theme preset `{preset}` for `{path}`"),
+                    Some(format!("expected one of {:?}", Theme::presets())),
+                ),
+                _ => (),
+            },
+            Kind::PositiveInteger => match item.as_integer() {
+                Some(value) if value <= 0 => self.report(
+                    Severity::Error,
+                    span,
+                    &format!("`{path}` should be positive, found {value}"),
+                    Some("use a value between 1 and 127".to_owned()),
+                ),
+                _ => (),
+            },
+        }
+    }
+}
+
+/// Returns the line and the column of a byte offset.
+fn position(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+
+    (line, column)
+}
+
+/// Checks if a value is a color understood by Tk.
+///
+/// Returns whether a valid color name is known, or nothing for an invalid color.
+/// Note that the system colors of a platform, like `SystemButtonFace`, are unknown.
+fn is_color(color: &str) -> Option<bool> {
+    match color.strip_prefix('#') {
+        Some(hex) => ([3, 6, 9, 12].contains(&hex.len())
+            && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .then_some(true),
+        None if !color.is_empty()
+            && color.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ') =>
+        {
+            let name = color.replace(' ', "").to_ascii_lowercase();
+
+            Some(COLOR_NAMES.contains(&name.trim_end_matches(|c: char| c.is_ascii_digit())))
+        }
+        None => None,
+    }
+}
+
+/// Returns the description of the type of a value.
+fn type_name(item: &Item) -> &'static str {
+    match item {
+        Item::None => "nothing",
+        Item::Value(Value::String(_)) => "a string",
+        Item::Value(Value::Integer(_)) => "an integer",
+        Item::Value(Value::Float(_)) => "a float",
+        Item::Value(Value::Boolean(_)) => "a boolean",
+        Item::Value(Value::Datetime(_)) => "a datetime",
+        Item::Value(Value::Array(_)) => "an array",
+        Item::Value(Value::InlineTable(_)) | Item::Table(_) => "a table",
+        Item::ArrayOfTables(_) => "an array of tables",
+    }
+}
+
+/// Levenshtein distance between two words.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Checker, Severity};
+    use std::path::Path;
+
+    #[test]
+    fn test_check() {
+        let content = r##"
+[core]
+buffer_size = -5
+auto_commit = false
+
+[theme]
+header.background = "#252320"
+header.foregrond = "#dedddd"
+header.font = { family = "Charis-SIL", size = 12, weight = "bold" }
+body.background = "#12"
+body.foreground = "#252320"
+body.font = { family = "Charis-SIL", size = 10, weight = "heavy" }
+
+[display]
+template = "{index}. {txt}"
+
+[theme.scripts]
+Nkoo = "Noto Sans NKo"
+Nkko = "Noto Sans NKo"
+Thaa = "MV Boli"
+
+[themes]
+"##;
+        let diagnostics = Checker::new(content).check();
+        let problems: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.severity,
+                    diagnostic.line,
+                    diagnostic.column,
+                    diagnostic.suggestion.is_some(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            problems,
+            vec![
+                (Severity::Error, 3, 15, true),
+                // Missing `header.foreground`.
+                (Severity::Error, 7, 1, false),
+                (Severity::Error, 8, 8, true),
+                (Severity::Error, 10, 19, true),
+                (Severity::Error, 12, 58, true),
+                (Severity::Warning, 15, 12, true),
+                (Severity::Error, 19, 1, true),
+                (Severity::Warning, 22, 2, true),
+            ]
+        );
+
+        // Syntax error.
+        let diagnostics = Checker::new("[core\nbuffer_size = 12").check();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 1);
+
+        // Missing keys.
+        let diagnostics = Checker::new("[info]\nname = \"test\"").check();
+        assert_eq!(diagnostics.len(), 4);
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == Severity::Error && diagnostic.line == 1));
+        let diagnostics = Checker::new("[info]\nname = \"test\"").partial().check();
+        assert_eq!(diagnostics, vec![]);
+
+        // Theme preset.
+        let diagnostics = Checker::new("[theme]\npreset = \"dark\"").check();
+        assert_eq!(diagnostics, vec![]);
+        let diagnostics = Checker::new("[theme]\npreset = \"drak\"").check();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].column, 10);
+        let diagnostics =
+            Checker::new("[theme]\npreset = \"dark\"\ncode.font.family = \"Charis-SIL\"").check();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].line, 0);
+        let diagnostics = Checker::new("[theme]\nheader.background = \"red\"").check();
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].line, 1);
+
+        // Wrong types and values.
+        let content = r##"
+[theme]
+preset = "dark"
+offset = -3
+header.background = "whte"
+header.font = { family = "Charis-SIL", size = "ten", weight = "bold", slant = "oblique" }
+
+[display]
+show_delay = "x"
+auto_hide = 1
+scaling = -2.0
+"##;
+        let problems: Vec<_> = Checker::new(content)
+            .check()
+            .iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.line, diagnostic.column))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                (Severity::Error, 4, 10),
+                (Severity::Warning, 5, 21),
+                (Severity::Error, 6, 47),
+                (Severity::Error, 6, 79),
+                (Severity::Error, 9, 14),
+                (Severity::Error, 10, 13),
+                (Severity::Error, 11, 11),
+            ]
+        );
+
+        // Valid files.
+        ["blank_sample", "full_sample", "sample"]
+            .iter()
+            .for_each(|name| {
+                let filepath = format!("./data/{name}.toml");
+                assert_eq!(check(Path::new(&filepath)).unwrap(), vec![]);
+            });
+    }
+}
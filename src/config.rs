@@ -1,22 +1,58 @@
-use anyhow::{Context, Result};
-use serde::Deserialize;
-use std::{fs, path::Path};
-use toml::{self};
+use super::data;
+use anyhow::{anyhow, Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::{env, fs, thread, time::Duration};
+use toml::{self, Table, Value};
 
-#[derive(Clone, Deserialize, Debug, Default)]
+/// Sections only used by afrim.
+const AFRIM_SECTIONS: &[&str] = &["data", "translators", "translation"];
+
+/// Sections used by afrim and afrim wish.
+///
+/// Since afrim doesn't merge the files, they are read from the file used by afrim.
+const SHARED_SECTIONS: &[&str] = &["core"];
+
+/// Themes shipped with afrim wish.
+///
+/// Each preset is written as the content of a `[theme]` section.
+const THEME_PRESETS: &[(&str, &str)] = &[
+    ("classic", include_str!("presets/classic.toml")),
+    ("dark", include_str!("presets/dark.toml")),
+    ("light", include_str!("presets/light.toml")),
+    ("high-contrast", include_str!("presets/high-contrast.toml")),
+    ("solarized", include_str!("presets/solarized.toml")),
+];
+
+/// Font family of the default themes.
+pub const DEFAULT_FAMILY: &str = "Charis-SIL";
+
+/// Configuration of afrim wish.
+///
+/// Note that the afrim sections are described by afrim.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug, Default)]
 pub struct Config {
     pub theme: Option<Theme>,
     pub core: Option<Core>,
     pub info: Option<Info>,
+    pub display: Option<Display>,
+    pub dictionary: Option<Dictionary>,
+    pub ranking: Option<Ranking>,
+    pub shortcodes: Option<Shortcodes>,
 }
 
-#[derive(Clone, Deserialize, Debug)]
+/// Core options, shared with afrim.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug)]
 pub struct Core {
     pub buffer_size: i8,
     pub auto_commit: bool,
 }
 
-#[derive(Clone, Deserialize, Debug)]
+/// Information about the configuration.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug)]
 pub struct Info {
     pub name: String,
     pub description: String,
@@ -25,33 +61,186 @@ pub struct Info {
     pub version: String,
 }
 
-#[derive(Clone, Deserialize, Debug)]
+/// Options of the personal dictionary.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct Dictionary {
+    /// Path of the dictionary, in the data directory by default.
+    pub path: Option<PathBuf>,
+}
+
+/// Options of the ranking of the predicates.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct Ranking {
+    /// Whether the commits are counted to propose the usual predicates first, false by default.
+    pub learn: Option<bool>,
+    /// Path of the frequencies, in the data directory by default.
+    pub path: Option<PathBuf>,
+}
+
+/// Options of the emojis and symbols proposed for a shortcode, eg. `:smile`.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct Shortcodes {
+    pub enabled: Option<bool>,
+}
+
+/// Display options of the tooltip.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct Display {
+    pub template: Option<String>,
+    pub commit_glyph: Option<String>,
+    pub auto_hide: Option<bool>,
+    pub show_delay: Option<u64>,
+    pub placement: Option<Placement>,
+    pub dock: Option<Corner>,
+    pub layout: Option<Layout>,
+    pub separator: Option<String>,
+    pub max_width: Option<usize>,
+    pub max_text_length: Option<usize>,
+    /// Scaling of the interface, detected from the screen when not provided.
+    pub scaling: Option<f64>,
+    pub direction: Option<Direction>,
+}
+
+/// Direction of the text of the predicates.
+#[derive(Clone, Copy, Deserialize, Serialize, JsonSchema, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Direction {
+    /// Detected from the text of each predicate.
+    #[default]
+    Auto,
+    /// Left to right.
+    Ltr,
+    /// Right to left.
+    Rtl,
+}
+
+/// Layout of the predicates.
+#[derive(Clone, Copy, Deserialize, Serialize, JsonSchema, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+    /// One predicate per line.
+    #[default]
+    Vertical,
+    /// Predicates on the same line, wrapped at the maximum width.
+    Horizontal,
+}
+
+/// Placement of the tooltip.
+#[derive(Clone, Copy, Deserialize, Serialize, JsonSchema, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Placement {
+    /// Follows the cursor.
+    #[default]
+    Cursor,
+    /// Stays at the same position, movable by the user.
+    Docked,
+}
+
+/// Corner of the screen.
+#[derive(Clone, Copy, Deserialize, Serialize, JsonSchema, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Theme of the tooltip.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug)]
 pub struct Theme {
+    /// Name of a built-in theme, whose keys can be overridden.
+    pub preset: Option<String>,
+    /// Required, unless defined by the preset.
+    #[schemars(with = "Option<SectionTheme>")]
     pub header: SectionTheme,
+    /// Required, unless defined by the preset.
+    #[schemars(with = "Option<SectionTheme>")]
     pub body: SectionTheme,
+    pub selected: Option<SectionTheme>,
+    pub anchor: Option<Anchor>,
+    pub offset: Option<u64>,
+    pub code: Option<HintTheme>,
+    pub remaining_code: Option<HintTheme>,
+    pub text: Option<HintTheme>,
+    pub can_commit: Option<HintTheme>,
+    pub toolkit: Option<ToolkitTheme>,
+    /// Font families of the text of the predicates, by ISO 15924 script code.
+    pub scripts: Option<BTreeMap<String, String>>,
+}
+
+/// Position of the tooltip relative to the cursor.
+#[derive(Clone, Copy, Deserialize, Serialize, JsonSchema, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Anchor {
+    #[default]
+    BelowRight,
+    BelowLeft,
+    AboveRight,
+    AboveLeft,
 }
 
-#[derive(Clone, Deserialize, Debug)]
+/// Theme of a section of the tooltip.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug)]
 pub struct SectionTheme {
     pub background: String,
     pub foreground: String,
     pub font: ThemeFont,
 }
 
-#[derive(Clone, Deserialize, Debug)]
+/// Theme of a part of a predicate.
+///
+/// The background is the one of the row of the predicate.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct HintTheme {
+    pub foreground: Option<String>,
+    pub font: Option<ThemeFont>,
+}
+
+/// Theme of the toolkit window.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct ToolkitTheme {
+    pub frame: Option<WidgetTheme>,
+    pub label: Option<WidgetTheme>,
+    pub button: Option<WidgetTheme>,
+    pub tab: Option<WidgetTheme>,
+    /// Button of the idle state.
+    pub idle: Option<WidgetTheme>,
+    /// Button of the running state.
+    pub running: Option<WidgetTheme>,
+}
+
+/// Theme of a widget of the toolkit.
+///
+/// The missing keys fallback on the default theme of the widget.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug, Default)]
+pub struct WidgetTheme {
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub font: Option<ThemeFont>,
+}
+
+/// Font of a text.
+#[derive(Clone, Deserialize, Serialize, JsonSchema, Debug)]
 pub struct ThemeFont {
     pub family: String,
+    /// Families used in order when the family is not installed.
+    pub fallbacks: Option<Vec<String>>,
     pub size: u64,
     pub weight: String,
+    pub slant: Option<Slant>,
+    pub underline: Option<bool>,
+    pub overstrike: Option<bool>,
 }
 
-impl Default for &Core {
-    fn default() -> Self {
-        &Core {
-            buffer_size: 64,
-            auto_commit: false,
-        }
-    }
+/// Slant of a font.
+#[derive(Clone, Copy, Deserialize, Serialize, JsonSchema, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Slant {
+    #[default]
+    Roman,
+    Italic,
 }
 
 impl Default for Core {
@@ -66,9 +255,13 @@ impl Default for Core {
 impl Default for Theme {
     fn default() -> Self {
         let font = ThemeFont {
-            family: "Charis-SIL".to_owned(),
+            family: DEFAULT_FAMILY.to_owned(),
+            fallbacks: None,
             size: 10,
             weight: "bold".to_owned(),
+            slant: None,
+            underline: None,
+            overstrike: None,
         };
         let header = SectionTheme {
             background: "#252320".to_owned(),
@@ -81,7 +274,303 @@ impl Default for Theme {
             font,
         };
 
-        Self { header, body }
+        Self {
+            preset: None,
+            header,
+            body,
+            selected: None,
+            anchor: None,
+            offset: None,
+            code: None,
+            remaining_code: None,
+            text: None,
+            can_commit: None,
+            toolkit: None,
+            scripts: None,
+        }
+    }
+}
+
+impl Theme {
+    /// Returns the names of the built-in themes.
+    pub fn presets() -> Vec<&'static str> {
+        THEME_PRESETS.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Returns the keys of a built-in theme.
+    fn preset(name: &str) -> Option<Table> {
+        THEME_PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, content)| toml::from_str(content).unwrap())
+    }
+
+    /// Returns the position of the tooltip relative to the cursor.
+    pub fn anchor(&self) -> Anchor {
+        self.anchor.unwrap_or_default()
+    }
+
+    /// Returns the theme of the selected predicate.
+    ///
+    /// Fallback on the header colors and the body font when not provided.
+    pub fn selected(&self) -> SectionTheme {
+        self.selected.to_owned().unwrap_or_else(|| SectionTheme {
+            background: self.header.background.to_owned(),
+            foreground: self.header.foreground.to_owned(),
+            font: self.body.font.to_owned(),
+        })
+    }
+
+    /// Returns the theme of the code already typed.
+    pub fn code(&self) -> HintTheme {
+        self.code.to_owned().unwrap_or_else(|| HintTheme {
+            foreground: Some("#7d7a75".to_owned()),
+            font: Some(ThemeFont {
+                weight: "normal".to_owned(),
+                ..self.body.font.to_owned()
+            }),
+        })
+    }
+
+    /// Returns the theme of the code remaining to type.
+    pub fn remaining_code(&self) -> HintTheme {
+        self.remaining_code.to_owned().unwrap_or_else(|| HintTheme {
+            foreground: Some("#1971c2".to_owned()),
+            font: None,
+        })
+    }
+
+    /// Returns the theme of the text of the predicate.
+    pub fn text(&self) -> HintTheme {
+        self.text.to_owned().unwrap_or_default()
+    }
+
+    /// Returns the theme of the mark of a predicate ready to be committed.
+    pub fn can_commit(&self) -> HintTheme {
+        self.can_commit.to_owned().unwrap_or_else(|| HintTheme {
+            foreground: Some("#2f9e44".to_owned()),
+            font: None,
+        })
+    }
+
+    /// Returns the theme of the toolkit window.
+    pub fn toolkit(&self) -> ToolkitTheme {
+        self.toolkit.to_owned().unwrap_or_default()
+    }
+
+    /// Returns the font of a text in a script, if configured.
+    ///
+    /// The font of the text in the section is used for the other properties,
+    /// and its families as fallbacks.
+    pub fn script_font(&self, script: &str, section: &SectionTheme) -> Option<ThemeFont> {
+        let family = self.scripts.as_ref()?.get(script)?;
+        let font = self.text().font.unwrap_or_else(|| section.font.to_owned());
+
+        Some(ThemeFont {
+            family: family.to_owned(),
+            fallbacks: Some(font.families()),
+            ..font
+        })
+    }
+
+    /// Returns the fonts used by the tooltip and the toolkit.
+    pub fn fonts(&self) -> Vec<ThemeFont> {
+        let section = |theme: &SectionTheme| theme.font.to_owned();
+        let hint = |theme: HintTheme| theme.font;
+        let toolkit = self.toolkit();
+
+        [
+            section(&self.header),
+            section(&self.body),
+            section(&self.selected()),
+            toolkit.frame().font,
+            toolkit.label().font,
+            toolkit.button().font,
+            toolkit.tab().font,
+            toolkit.idle().font,
+            toolkit.running().font,
+        ]
+        .into_iter()
+        .chain(
+            [
+                self.code(),
+                self.remaining_code(),
+                self.text(),
+                self.can_commit(),
+            ]
+            .into_iter()
+            .filter_map(hint),
+        )
+        .collect()
+    }
+}
+
+impl Dictionary {
+    /// Returns the path of the dictionary file.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path
+            .to_owned()
+            .or_else(|| data::default_path("dictionary.toml"))
+    }
+}
+
+impl Ranking {
+    pub fn learn(&self) -> bool {
+        self.learn.unwrap_or_default()
+    }
+
+    /// Returns the path of the frequencies file.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path
+            .to_owned()
+            .or_else(|| data::default_path("ranking.toml"))
+    }
+}
+
+impl Shortcodes {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or_default()
+    }
+}
+
+impl ThemeFont {
+    /// Returns the family of the font, followed by its fallbacks.
+    ///
+    /// By default, the fallbacks are fonts with a good coverage of the diacritics,
+    /// then fonts installed with Windows and macOS.
+    pub fn families(&self) -> Vec<String> {
+        let fallbacks = self.fallbacks.to_owned().unwrap_or_else(|| {
+            [
+                "Charis SIL",
+                "Gentium Plus",
+                "Noto Sans",
+                "DejaVu Sans",
+                "Segoe UI",
+                "Arial",
+                "Helvetica",
+            ]
+            .map(str::to_owned)
+            .to_vec()
+        });
+
+        [vec![self.family.to_owned()], fallbacks].concat()
+    }
+}
+
+impl ToolkitTheme {
+    pub fn frame(&self) -> SectionTheme {
+        Self::widget(&self.frame, "#1e1e1e", "#ffffff", "normal")
+    }
+
+    pub fn label(&self) -> SectionTheme {
+        Self::widget(&self.label, "#1e1e1e", "#ffffff", "normal")
+    }
+
+    pub fn button(&self) -> SectionTheme {
+        Self::widget(&self.button, "#ffffff", "#1e1e1e", "normal")
+    }
+
+    pub fn tab(&self) -> SectionTheme {
+        Self::widget(&self.tab, "#ffffff", "#1e1e1e", "normal")
+    }
+
+    pub fn idle(&self) -> SectionTheme {
+        Self::widget(&self.idle, "#e03131", "#1e1e1e", "bold")
+    }
+
+    pub fn running(&self) -> SectionTheme {
+        Self::widget(&self.running, "#1971c2", "#1e1e1e", "bold")
+    }
+
+    /// Returns the theme of a widget, completed with its default values.
+    fn widget(
+        theme: &Option<WidgetTheme>,
+        background: &str,
+        foreground: &str,
+        weight: &str,
+    ) -> SectionTheme {
+        let theme = theme.to_owned().unwrap_or_default();
+
+        SectionTheme {
+            background: theme.background.unwrap_or_else(|| background.to_owned()),
+            foreground: theme.foreground.unwrap_or_else(|| foreground.to_owned()),
+            font: theme.font.unwrap_or_else(|| ThemeFont {
+                family: DEFAULT_FAMILY.to_owned(),
+                fallbacks: None,
+                size: 9,
+                weight: weight.to_owned(),
+                slant: None,
+                underline: None,
+                overstrike: None,
+            }),
+        }
+    }
+
+    /// Returns the theme with the default values filled.
+    fn resolved(&self) -> Self {
+        let widget = |theme: SectionTheme| {
+            Some(WidgetTheme {
+                background: Some(theme.background),
+                foreground: Some(theme.foreground),
+                font: Some(theme.font),
+            })
+        };
+
+        Self {
+            frame: widget(self.frame()),
+            label: widget(self.label()),
+            button: widget(self.button()),
+            tab: widget(self.tab()),
+            idle: widget(self.idle()),
+            running: widget(self.running()),
+        }
+    }
+}
+
+impl Display {
+    /// Returns whether the tooltip is hidden when there is nothing to display.
+    pub fn auto_hide(&self) -> bool {
+        self.auto_hide.unwrap_or(true)
+    }
+
+    /// Returns the template of a predicate line.
+    pub fn template(&self) -> String {
+        self.template
+            .to_owned()
+            .unwrap_or_else(|| "{index}. {text}[ ~{remaining_code}][ {can_commit}]".to_owned())
+    }
+
+    /// Returns the separator of the predicates in an horizontal layout.
+    pub fn separator(&self) -> String {
+        self.separator.to_owned().unwrap_or_else(|| "  ".to_owned())
+    }
+
+    /// Returns the mark of a predicate ready to be committed.
+    pub fn commit_glyph(&self) -> String {
+        self.commit_glyph
+            .to_owned()
+            .unwrap_or_else(|| "✓".to_owned())
+    }
+
+    /// Returns the delay in milliseconds before showing the tooltip.
+    pub fn show_delay(&self) -> u64 {
+        self.show_delay.unwrap_or_default()
+    }
+
+    pub fn placement(&self) -> Placement {
+        self.placement.unwrap_or_default()
+    }
+
+    pub fn dock(&self) -> Corner {
+        self.dock.unwrap_or_default()
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout.unwrap_or_default()
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction.unwrap_or_default()
     }
 }
 
@@ -98,21 +587,293 @@ impl Default for Info {
 }
 
 impl Config {
+    /// Returns the configuration with the default values filled.
+    ///
+    /// Note that the values without default, like the offset of the tooltip, stay empty.
+    pub fn resolved(&self) -> Self {
+        let theme = self.theme.to_owned().unwrap_or_default();
+        let display = self.display.to_owned().unwrap_or_default();
+        let ranking = self.ranking.to_owned().unwrap_or_default();
+
+        Self {
+            theme: Some(Theme {
+                selected: Some(theme.selected()),
+                anchor: Some(theme.anchor()),
+                code: Some(theme.code()),
+                remaining_code: Some(theme.remaining_code()),
+                text: Some(theme.text()),
+                can_commit: Some(theme.can_commit()),
+                toolkit: Some(theme.toolkit().resolved()),
+                ..theme
+            }),
+            core: Some(self.core.to_owned().unwrap_or_default()),
+            info: Some(self.info.to_owned().unwrap_or_default()),
+            display: Some(Display {
+                template: Some(display.template()),
+                commit_glyph: Some(display.commit_glyph()),
+                auto_hide: Some(display.auto_hide()),
+                show_delay: Some(display.show_delay()),
+                placement: Some(display.placement()),
+                dock: Some(display.dock()),
+                layout: Some(display.layout()),
+                direction: Some(display.direction()),
+                separator: Some(display.separator()),
+                ..display
+            }),
+            dictionary: Some(Dictionary {
+                path: self.dictionary.to_owned().unwrap_or_default().path(),
+            }),
+            ranking: Some(Ranking {
+                learn: Some(ranking.learn()),
+                path: ranking.path(),
+            }),
+            shortcodes: Some(Shortcodes {
+                enabled: Some(self.shortcodes.to_owned().unwrap_or_default().enabled()),
+            }),
+        }
+    }
+
     pub fn from_file(filepath: &Path) -> Result<Self> {
-        let content = fs::read_to_string(filepath)
-            .with_context(|| format!("Couldn't open file {filepath:?}"))?;
-        let config: Self = toml::from_str(&content)
+        let mut table = read_table(filepath)?;
+        apply_preset(&mut table)?;
+        let config: Self = Value::Table(table)
+            .try_into()
             .with_context(|| format!("Failed to parse configuration file {filepath:?}"))?;
 
         Ok(config)
     }
+
+    /// Returns the configuration files to merge, from the lowest priority.
+    ///
+    /// In order: the system file, the user file, the portable file next to the
+    /// executable and the given file. Only the given file can be missing.
+    pub fn layers(filepath: Option<&Path>) -> Vec<PathBuf> {
+        let system_file = if cfg!(windows) {
+            env::var_os("ProgramData").map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            Some(PathBuf::from("/Library/Application Support"))
+        } else {
+            Some(PathBuf::from("/etc"))
+        }
+        .map(|dir| dir.join(env!("CARGO_PKG_NAME")).join("config.toml"));
+        let user_file =
+            dirs::config_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join("config.toml"));
+        let portable_file = env::current_exe().ok().and_then(|exe| {
+            exe.parent()
+                .map(|dir| dir.join(concat!(env!("CARGO_PKG_NAME"), ".toml")))
+        });
+
+        [system_file, user_file, portable_file]
+            .into_iter()
+            .flatten()
+            .filter(|filepath| filepath.is_file())
+            .chain(filepath.map(Path::to_owned))
+            .collect()
+    }
+
+    /// Loads the configuration from several files.
+    ///
+    /// Each file overrides the keys of the previous ones.
+    pub fn from_layers(filepaths: &[PathBuf]) -> Result<Self> {
+        let mut table = Self::merge_layers(filepaths)?;
+        apply_preset(&mut table)?;
+        let config: Self = Value::Table(table)
+            .try_into()
+            .context("Failed to parse the merged configuration")?;
+
+        Ok(config)
+    }
+
+    fn merge_layers(filepaths: &[PathBuf]) -> Result<Table> {
+        if filepaths.is_empty() {
+            return Err(anyhow!("No configuration file found"));
+        }
+
+        let mut table =
+            filepaths
+                .iter()
+                .try_fold(Table::new(), |mut table, filepath| -> Result<Table> {
+                    merge(&mut table, read_table(filepath)?);
+
+                    Ok(table)
+                })?;
+        // Not empty since the files are merged.
+        let afrim_table = read_table(Self::afrim_layer(filepaths).unwrap())?;
+
+        SHARED_SECTIONS
+            .iter()
+            .for_each(|section| match afrim_table.get(*section) {
+                Some(value) => {
+                    table.insert(section.to_string(), value.to_owned());
+                }
+                None => {
+                    table.remove(*section);
+                }
+            });
+
+        Ok(table)
+    }
+
+    /// Returns the file to use for afrim.
+    ///
+    /// Since afrim doesn't merge the files, we use the file with the highest
+    /// priority which has an afrim section, or else the last file.
+    pub fn afrim_layer(filepaths: &[PathBuf]) -> Option<&Path> {
+        filepaths
+            .iter()
+            .rev()
+            .find(|filepath| {
+                read_table(filepath).is_ok_and(|table| {
+                    AFRIM_SECTIONS
+                        .iter()
+                        .any(|section| table.contains_key(*section))
+                })
+            })
+            .or(filepaths.last())
+            .map(PathBuf::as_path)
+    }
+
+    /// Returns each value of the merged configuration, with the file it comes from.
+    ///
+    /// Note that the afrim sections are omitted.
+    pub fn origins(filepaths: &[PathBuf]) -> Result<Vec<(String, Value, PathBuf)>> {
+        let layers = filepaths
+            .iter()
+            .map(|filepath| Ok((filepath, read_table(filepath)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut values = vec![];
+
+        flatten(&Self::merge_layers(filepaths)?, &[], &mut values);
+        let afrim_file = Self::afrim_layer(filepaths);
+
+        Ok(values
+            .into_iter()
+            .filter(|(keys, _)| !AFRIM_SECTIONS.contains(&keys[0].as_str()))
+            .filter_map(|(keys, value)| {
+                let is_shared = SHARED_SECTIONS.contains(&keys[0].as_str());
+                let (filepath, _) = layers
+                    .iter()
+                    .rev()
+                    .filter(|(filepath, _)| !is_shared || Some(filepath.as_path()) == afrim_file)
+                    .find(|(_, table)| lookup(table, &keys).is_some())?;
+
+                Some((keys.join("."), value, filepath.to_path_buf()))
+            })
+            .collect())
+    }
+
+    /// Watches the configuration files and sends the merged configuration at each change.
+    ///
+    /// Note that the files are polled every second.
+    pub fn watch(filepaths: &[PathBuf]) -> Receiver<Result<Self>> {
+        let (tx, rx) = mpsc::channel();
+        let filepaths = filepaths.to_owned();
+        let modified = |filepaths: &[PathBuf]| {
+            filepaths
+                .iter()
+                .map(|filepath| {
+                    fs::metadata(filepath)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        thread::spawn(move || {
+            let mut last_modified = modified(&filepaths);
+
+            loop {
+                thread::sleep(Duration::from_secs(1));
+
+                let current_modified = modified(&filepaths);
+                if current_modified == last_modified {
+                    continue;
+                }
+                last_modified = current_modified;
+
+                // The watcher is no longer needed.
+                if tx.send(Self::from_layers(&filepaths)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        rx
+    }
+}
+
+fn read_table(filepath: &Path) -> Result<Table> {
+    let content =
+        fs::read_to_string(filepath).with_context(|| format!("Couldn't open file {filepath:?}"))?;
+
+    toml::from_str(&content)
+        .with_context(|| format!("Failed to parse configuration file {filepath:?}"))
+}
+
+/// Fills the theme with the keys of its preset.
+///
+/// Note that the keys of the theme override those of the preset.
+pub(crate) fn apply_preset(table: &mut Table) -> Result<()> {
+    let Some(Value::Table(theme)) = table.get_mut("theme") else {
+        return Ok(());
+    };
+    let Some(name) = theme.get("preset").and_then(Value::as_str) else {
+        return Ok(());
+    };
+    let mut preset =
+        Theme::preset(name).with_context(|| format!("Unknown theme preset `{name}`"))?;
+
+    merge(&mut preset, std::mem::take(theme));
+    *theme = preset;
+
+    Ok(())
+}
+
+/// Merges a table into another.
+///
+/// The nested tables are merged key by key, and the other values are replaced.
+pub(crate) fn merge(base: &mut Table, layer: Table) {
+    for (key, value) in layer {
+        let value = match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(layer)) => {
+                merge(base, layer);
+                continue;
+            }
+            (_, value) => value,
+        };
+
+        base.insert(key, value);
+    }
+}
+
+/// Lists the values of a table which are not tables, with their keys.
+fn flatten(table: &Table, keys: &[String], values: &mut Vec<(Vec<String>, Value)>) {
+    table.iter().for_each(|(key, value)| {
+        let keys = [keys, &[key.to_owned()]].concat();
+
+        match value {
+            Value::Table(table) => flatten(table, &keys, values),
+            value => values.push((keys, value.to_owned())),
+        }
+    });
+}
+
+fn lookup<'a>(table: &'a Table, keys: &[String]) -> Option<&'a Value> {
+    let (key, keys) = keys.split_first()?;
+    let value = table.get(key)?;
+
+    match value {
+        Value::Table(table) if !keys.is_empty() => lookup(table, keys),
+        value if keys.is_empty() => Some(value),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     #[test]
     fn from_file() {
-        use crate::config::Config;
+        use crate::config::{Config, Display};
         use std::path::Path;
 
         let config = Config::from_file(Path::new("./data/blank_sample.toml"));
@@ -123,6 +884,16 @@ mod tests {
         config.core.unwrap_or_default();
         config.theme.unwrap_or_default();
         config.info.unwrap_or_default();
+        config.display.unwrap_or_default();
+
+        // Dump the default configuration.
+        let config = Config::default().resolved();
+        let content = toml::to_string(&config).unwrap();
+        let config: Config = toml::from_str(&content).unwrap();
+        assert_eq!(
+            config.display.unwrap().template,
+            Some(Display::default().template())
+        );
 
         let config = Config::from_file(Path::new("./data/full_sample.toml"));
         assert!(config.is_ok());
@@ -130,4 +901,65 @@ mod tests {
         let config = Config::from_file(Path::new("./data/sample.toml"));
         assert!(config.is_ok());
     }
+
+    #[test]
+    fn from_layers() {
+        use crate::config::Config;
+        use std::path::PathBuf;
+
+        let layers = [
+            PathBuf::from("./data/full_sample.toml"),
+            PathBuf::from("./data/sample.toml"),
+        ];
+        let config = Config::from_layers(&layers).unwrap();
+        assert_eq!(config.theme.unwrap().header.background, "#252320");
+        // As afrim, the core comes from the last file.
+        assert!(config.core.is_none());
+        assert!(Config::from_layers(&[]).is_err());
+
+        let origins = Config::origins(&layers).unwrap();
+        let origin = |key: &str| {
+            origins
+                .iter()
+                .find(|(name, _, _)| name == key)
+                .map(|(_, _, filepath)| filepath.to_owned())
+        };
+        assert_eq!(origin("info.name"), Some(layers[1].to_owned()));
+        assert_eq!(origin("theme.offset"), Some(layers[0].to_owned()));
+        assert_eq!(origin("core.buffer_size"), None);
+
+        let layers = [layers[1].to_owned(), layers[0].to_owned()];
+        let origins = Config::origins(&layers).unwrap();
+        assert!(origins
+            .iter()
+            .any(|(name, _, filepath)| name == "core.buffer_size" && *filepath == layers[1]));
+    }
+
+    #[test]
+    fn presets() {
+        use crate::config::{apply_preset, Config, Theme};
+        use toml::{Table, Value};
+
+        let load = |content: &str| {
+            let mut table: Table = toml::from_str(content).unwrap();
+            apply_preset(&mut table)?;
+            let config: Config = Value::Table(table).try_into()?;
+
+            anyhow::Ok(config)
+        };
+
+        // Each preset is a complete theme.
+        Theme::presets().iter().for_each(|name| {
+            let config = load(&format!("[theme]\npreset = \"{name}\"")).unwrap();
+            assert_eq!(config.theme.unwrap().preset.as_deref(), Some(*name));
+        });
+
+        // The keys of the theme override the preset.
+        let config = load("[theme]\npreset = \"dark\"\nheader.background = \"#123456\"").unwrap();
+        let theme = config.theme.unwrap();
+        assert_eq!(theme.header.background, "#123456");
+        assert_eq!(theme.body.background, "#252526");
+
+        assert!(load("[theme]\npreset = \"unknown\"").is_err());
+    }
 }
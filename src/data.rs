@@ -0,0 +1,36 @@
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Returns the default path of a file kept in the data directory.
+pub fn default_path(filename: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(filename))
+}
+
+/// Loads a toml data file, described by its name in the errors.
+///
+/// Note that the default value is returned if the file doesn't exist yet.
+pub fn load<T: DeserializeOwned + Default>(filepath: &Path, name: &str) -> Result<T> {
+    if !filepath.exists() {
+        return Ok(T::default());
+    }
+
+    let content =
+        fs::read_to_string(filepath).with_context(|| format!("Couldn't open file {filepath:?}"))?;
+
+    toml::from_str(&content).with_context(|| format!("Failed to parse {name} file {filepath:?}"))
+}
+
+/// Saves a toml data file, creating its directory if needed.
+pub fn save<T: Serialize>(value: &T, filepath: &Path, name: &str) -> Result<()> {
+    let content =
+        toml::to_string(value).with_context(|| format!("Failed to serialize the {name}"))?;
+
+    if let Some(dir) = filepath.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Couldn't create {dir:?}"))?;
+    }
+    fs::write(filepath, content).with_context(|| format!("Couldn't write file {filepath:?}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use super::data;
+use afrim::frontend::Predicate;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Personal words of the user, by code.
+///
+/// Stored as a toml file, eg. `yaounde = ["Yaoundé"]`.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct UserDictionary {
+    entries: BTreeMap<String, Vec<String>>,
+}
+
+impl UserDictionary {
+    pub fn load(filepath: &Path) -> Result<Self> {
+        data::load(filepath, "dictionary")
+    }
+
+    pub fn save(&self, filepath: &Path) -> Result<()> {
+        data::save(self, filepath, "dictionary")
+    }
+
+    /// Adds a text to a code, if not yet present.
+    pub fn add(&mut self, code: &str, text: &str) {
+        let texts = self.entries.entry(code.to_owned()).or_default();
+
+        if !texts.iter().any(|other| other == text) {
+            texts.push(text.to_owned());
+        }
+    }
+
+    /// Removes a code and its texts.
+    ///
+    /// Returns whether the code was present.
+    pub fn remove(&mut self, code: &str) -> bool {
+        self.entries.remove(code).is_some()
+    }
+
+    /// Returns the predicates of the codes starting with the input.
+    pub fn predicates(&self, input: &str) -> Vec<Predicate> {
+        if input.is_empty() {
+            return vec![];
+        }
+
+        self.entries
+            .range(input.to_owned()..)
+            .take_while(|(code, _)| code.starts_with(input))
+            .flat_map(|(code, texts)| {
+                texts
+                    .iter()
+                    .map(|text| predicate(input, &code[input.len()..], text))
+            })
+            .collect()
+    }
+}
+
+/// Returns a predicate proposed by afrim wish, completing the code typed.
+///
+/// Note that these predicates are never committed automatically.
+pub fn predicate(code: &str, remaining_code: &str, text: &str) -> Predicate {
+    Predicate {
+        code: code.to_owned(),
+        remaining_code: remaining_code.to_owned(),
+        texts: vec![text.to_owned()],
+        can_commit: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserDictionary;
+
+    #[test]
+    fn test_dictionary() {
+        let mut dictionary: UserDictionary =
+            toml::from_str("yaounde = [\"Yaoundé\"]\nya = [\"ya\", \"yà\"]").unwrap();
+        dictionary.add("douala", "Douala");
+        dictionary.add("ya", "yà");
+
+        let predicates = dictionary.predicates("ya");
+        let texts: Vec<_> = predicates
+            .iter()
+            .map(|predicate| predicate.texts[0].as_str())
+            .collect();
+        assert_eq!(texts, vec!["ya", "yà", "Yaoundé"]);
+        assert_eq!(predicates[2].code, "ya");
+        assert_eq!(predicates[2].remaining_code, "ounde");
+        assert_eq!(dictionary.predicates("d").len(), 1);
+        assert_eq!(dictionary.predicates("").len(), 0);
+        assert_eq!(dictionary.predicates("z").len(), 0);
+
+        assert!(dictionary.remove("douala"));
+        assert!(!dictionary.remove("douala"));
+        let content = toml::to_string(&dictionary).unwrap();
+        assert_eq!(
+            toml::from_str::<UserDictionary>(&content).unwrap(),
+            dictionary
+        );
+    }
+}
@@ -1,17 +1,32 @@
+mod check;
 mod config;
+mod data;
+mod dictionary;
+mod ranking;
+mod shortcode;
+mod state;
 mod window;
 
 use afrim::frontend::{Command, Frontend};
 use afrish::*;
 use anyhow::{anyhow, Result};
+use config::DEFAULT_FAMILY;
+use std::path::PathBuf;
 use std::sync::{
-    mpsc::{Receiver, Sender},
+    mpsc::{Receiver, RecvTimeoutError, Sender},
     OnceLock,
 };
 use std::thread;
-use window::{toolkit::ToolKit, tooltip::ToolTip};
+use std::time::Duration;
+use window::{font, scaling, toolkit::ToolKit, tooltip::ToolTip};
 
-pub use config::Config;
+pub use check::{check, check_layer, Diagnostic, Severity};
+pub use config::{Config, Theme};
+pub use dictionary::UserDictionary;
+pub use ranking::Frequencies;
+
+/// Delay between two checks of the actions of the user, while afrim is idle.
+const ACTIONS_INTERVAL: Duration = Duration::from_millis(50);
 
 pub struct Wish {
     window: &'static afrish::TkTopLevel,
@@ -19,6 +34,15 @@ pub struct Wish {
     toolkit: ToolKit,
     tx: Option<Sender<Command>>,
     rx: Option<Receiver<Command>>,
+    config_watcher: Option<Receiver<Result<Config>>>,
+    config: Config,
+    /// Density of the screen reported by Tk, before any scaling.
+    dpi: f64,
+    screen: Option<(u64, u64)>,
+    /// Scaling of the interface, unscaled until the screen is known.
+    scaling: f64,
+    /// Warnings about the fonts, last reported.
+    font_warnings: Vec<String>,
 }
 
 impl Wish {
@@ -45,18 +69,164 @@ impl Wish {
 
     pub fn from_config(config: config::Config) -> Self {
         let wish = Self::init();
-        let tooltip = ToolTip::new(config.theme.to_owned().unwrap_or_default());
+        let tooltip = ToolTip::new(config.to_owned());
         let toolkit = ToolKit::new(config.to_owned());
 
-        Wish {
+        let mut wish = Wish {
             window: wish,
             tooltip,
             toolkit,
             tx: None,
             rx: None,
+            config_watcher: None,
+            config,
+            dpi: 0.0,
+            screen: None,
+            scaling: 1.0,
+            font_warnings: vec![],
+        };
+        wish.load_dictionary();
+        wish.load_frequencies();
+
+        wish
+    }
+
+    /// Loads the personal dictionary of the user.
+    ///
+    /// Note that an invalid dictionary is reported and ignored.
+    fn load_dictionary(&mut self) {
+        let Some(filepath) = self.config.dictionary.to_owned().unwrap_or_default().path() else {
+            return;
+        };
+
+        match UserDictionary::load(&filepath) {
+            Ok(dictionary) => self.tooltip.set_dictionary(dictionary),
+            Err(err) => Self::raise_warning("Problem loading dictionary file", &err),
+        }
+    }
+
+    /// Loads the frequencies of the commits of the user, if the learning is enabled.
+    ///
+    /// Note that invalid frequencies are reported and ignored.
+    fn load_frequencies(&mut self) {
+        let ranking = self.config.ranking.to_owned().unwrap_or_default();
+        let filepath = ranking.path().filter(|_| ranking.learn());
+        let frequencies = match &filepath {
+            Some(filepath) => Frequencies::load(filepath).unwrap_or_else(|err| {
+                Self::raise_warning("Problem loading ranking file", &err);
+
+                Frequencies::default()
+            }),
+            None => Frequencies::default(),
+        };
+
+        self.tooltip.set_frequencies(frequencies, filepath);
+    }
+
+    /// Reloads the configuration at each change of the configuration files.
+    pub fn watch_config(&mut self, filepaths: &[PathBuf]) {
+        self.config_watcher = Some(Config::watch(filepaths));
+    }
+
+    /// Applies the last valid configuration, if any.
+    ///
+    /// Note that an invalid configuration is reported and ignored.
+    fn reload_config(&mut self) {
+        let Some(config) = self
+            .config_watcher
+            .as_ref()
+            .and_then(|config_watcher| config_watcher.try_recv().ok())
+        else {
+            return;
+        };
+
+        match config {
+            Ok(config) => {
+                self.config = config;
+                self.update_scaling();
+                self.reload();
+                self.load_dictionary();
+                self.load_frequencies();
+            }
+            Err(err) => Self::raise_warning("Problem reloading config file", &err),
         }
     }
 
+    /// Rebuilds the windows with the current configuration.
+    fn reload(&mut self) {
+        self.tooltip.reload(self.config.to_owned());
+        self.toolkit.reload(self.config.to_owned());
+        self.check_fonts();
+    }
+
+    /// Warns about the fonts which are not installed.
+    ///
+    /// Note that the fonts replaced by a fallback and the default fonts are only reported
+    /// on the standard error, and that the same warnings are reported once.
+    fn check_fonts(&mut self) {
+        let fonts = self.config.theme.to_owned().unwrap_or_default().fonts();
+        let warnings = font::warnings(&fonts);
+
+        if warnings == self.font_warnings {
+            return;
+        }
+        warnings
+            .iter()
+            .for_each(|warning| eprintln!("warning: {warning}"));
+        self.font_warnings = warnings;
+
+        let mut missing_families: Vec<_> = fonts
+            .iter()
+            .filter(|font| font.family != DEFAULT_FAMILY)
+            .filter(|font| font::family(font, font::installed_families()).is_none())
+            .map(|font| format!("`{}`", font.family))
+            .collect();
+        missing_families.sort();
+        missing_families.dedup();
+
+        if !missing_families.is_empty() {
+            Self::raise_warning(
+                "Some fonts are not installed",
+                anyhow!(
+                    "The fonts {} and their fallbacks are not installed, Tk picks a font instead.",
+                    missing_families.join(", ")
+                ),
+            );
+        }
+    }
+
+    /// Handles the actions of the user on the tooltip.
+    fn handle_actions(&mut self) {
+        if let Err(err) = self.tooltip.handle_actions() {
+            Self::raise_warning("Problem saving state file", &err);
+        }
+    }
+
+    /// Applies the scaling of the configuration, or else the one of the screen.
+    ///
+    /// Returns whether the scaling changed.
+    /// Note that a scaling which isn't positive is ignored.
+    fn update_scaling(&mut self) -> bool {
+        let scaling = self
+            .config
+            .display
+            .as_ref()
+            .and_then(|display| display.scaling)
+            .filter(|scaling| scaling.is_finite() && *scaling > 0.0)
+            .or_else(|| self.screen.map(|screen| scaling::detect(self.dpi, screen)))
+            .unwrap_or(1.0);
+
+        if scaling == self.scaling {
+            return false;
+        }
+        self.scaling = scaling;
+
+        scaling::apply(scaling);
+        self.toolkit.set_scaling(scaling);
+
+        true
+    }
+
     pub fn raise_error<T: std::fmt::Debug>(message: &str, detail: T) {
         afrish::message_box()
             .parent(Self::init())
@@ -68,9 +238,42 @@ impl Wish {
         Self::kill();
     }
 
+    /// Shows a warning without interrupting the application.
+    ///
+    /// Note that the message box is managed by wish, so we don't wait for it.
+    pub fn raise_warning<T: std::fmt::Debug>(message: &str, detail: T) {
+        let quote = |text: &str| {
+            let text = text
+                .chars()
+                .map(|c| match c {
+                    '\\' | '"' | '[' | ']' | '$' | '{' | '}' => format!("\\{c}"),
+                    '\n' => "\\n".to_owned(),
+                    c => c.to_string(),
+                })
+                .collect::<String>();
+
+            format!("\"{text}\"")
+        };
+
+        afrish::tell_wish(&format!(
+            "after idle {{tk_messageBox -parent {} -icon warning -title Warning -message {} -detail {}}}",
+            Self::init().id(),
+            quote(message),
+            quote(&format!("{detail:?}"))
+        ));
+    }
+
     fn build(&mut self) {
+        // The density changes once the scaling is applied.
+        self.dpi = scaling::screen_dpi();
+        // Tk may start with another density than the reference one.
+        if !self.update_scaling() {
+            scaling::apply(self.scaling);
+        }
+
         self.tooltip.build(afrish::make_toplevel(self.window));
         self.toolkit.build(self.window.to_owned());
+        self.check_fonts();
     }
 
     /// End the process (wish and rust).
@@ -97,12 +300,30 @@ impl Frontend for Wish {
         // We shouldn't forget to listen for GUI events.
         thread::spawn(afrish::mainloop);
 
-        let tx = self.tx.as_ref().unwrap();
+        let tx = self.tx.to_owned().unwrap();
 
         loop {
-            let command = self.rx.as_ref().unwrap().recv()?;
+            let command = match self.rx.as_ref().unwrap().recv_timeout(ACTIONS_INTERVAL) {
+                Ok(command) => command,
+                // The clicks on the tooltip are handled without waiting for afrim.
+                Err(RecvTimeoutError::Timeout) => {
+                    self.handle_actions();
+
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            self.reload_config();
+
             match command {
-                Command::ScreenSize(screen) => self.tooltip.update_screen(screen),
+                Command::ScreenSize(screen) => {
+                    self.tooltip.update_screen(screen);
+                    self.screen = Some(screen);
+
+                    if self.update_scaling() {
+                        self.reload();
+                    }
+                }
                 Command::Position(position) => self.tooltip.update_position(position),
                 Command::InputText(input) => self.tooltip.set_input_text(input),
                 Command::PageSize(size) => self.tooltip.set_page_size(size),
@@ -113,13 +334,22 @@ impl Frontend for Wish {
                 Command::SelectPreviousPredicate => self.tooltip.select_previous_predicate(),
                 Command::SelectNextPredicate => self.tooltip.select_next_predicate(),
                 Command::SelectedPredicate => {
-                    if let Some(predicate) = self.tooltip.get_selected_predicate() {
-                        tx.send(Command::Predicate(predicate.to_owned()))?;
+                    // A predicate selected from the tooltip is committed.
+                    self.handle_actions();
+                    let predicate = self.tooltip.get_selected_predicate().cloned();
+
+                    if let Some(predicate) = predicate {
+                        if let Err(err) = self.tooltip.learn(&predicate) {
+                            Self::raise_warning("Problem saving ranking file", &err);
+                        }
+                        tx.send(Command::Predicate(predicate))?;
                     } else {
                         tx.send(Command::NoPredicate)?;
                     }
                 }
                 Command::NOP => {
+                    self.handle_actions();
+
                     if let Some(state) = self.toolkit.new_idle_state() {
                         tx.send(Command::State(state))?;
                     } else {
@@ -140,8 +370,11 @@ impl Frontend for Wish {
 
 #[cfg(test)]
 mod tests {
+    use crate::config::Ranking;
+    use crate::window::tooltip::Action;
     use crate::{Config, Wish};
     use afrim::frontend::{Command, Frontend, Predicate};
+    use std::fs;
     use std::path::Path;
     use std::sync::mpsc;
     use std::thread;
@@ -149,9 +382,16 @@ mod tests {
 
     #[test]
     fn test_api() {
-        let config = Config::from_file(Path::new("data/full_sample.toml")).unwrap();
+        let mut config = Config::from_file(Path::new("data/full_sample.toml")).unwrap();
+        let ranking_path = std::env::temp_dir().join("afrim-wish-test-ranking.toml");
+        fs::remove_file(&ranking_path).ok();
+        config.ranking = Some(Ranking {
+            learn: Some(true),
+            path: Some(ranking_path.to_owned()),
+        });
         let mut afrim_wish = Wish::from_config(config);
         assert!(afrim_wish.listen().is_err());
+        let actions = afrim_wish.tooltip.actions();
         let (tx1, rx1) = mpsc::channel();
         let (tx2, rx2) = mpsc::channel();
 
@@ -246,8 +486,81 @@ mod tests {
                 can_commit: false,
             })
         );
+
+        // Test the mouse.
+        //
+        // Note that the list of predicates was replaced once.
+        actions.lock().unwrap().push(Action::Select(3, 0));
+        actions.lock().unwrap().push(Action::Select(1, 1));
+        thread::sleep(Duration::from_millis(200));
+        tx1.send(Command::SelectedPredicate).unwrap();
+        assert_eq!(
+            rx2.recv().unwrap(),
+            Command::Predicate(Predicate {
+                code: "test1".to_owned(),
+                remaining_code: "23".to_owned(),
+                texts: vec!["ok".to_owned()],
+                can_commit: false,
+            })
+        );
+        actions.lock().unwrap().push(Action::Select(2, 1));
+        thread::sleep(Duration::from_millis(200));
+        tx1.send(Command::SelectedPredicate).unwrap();
+        assert_eq!(
+            rx2.recv().unwrap(),
+            Command::Predicate(Predicate {
+                code: "test12".to_owned(),
+                remaining_code: "3".to_owned(),
+                texts: vec!["ok".to_owned()],
+                can_commit: false,
+            })
+        );
+        // The keyboard selection follows the selection with the mouse.
+        tx1.send(Command::SelectNextPredicate).unwrap();
+        tx1.send(Command::SelectedPredicate).unwrap();
+        assert_eq!(
+            rx2.recv().unwrap(),
+            Command::Predicate(Predicate {
+                code: "test123".to_owned(),
+                remaining_code: "".to_owned(),
+                texts: vec!["ok".to_owned()],
+                can_commit: false,
+            })
+        );
         tx1.send(Command::Update).unwrap();
 
+        // Test the ranking.
+        let predicate = |text: &str| Predicate {
+            code: "ra".to_owned(),
+            remaining_code: "nk".to_owned(),
+            texts: vec![text.to_owned()],
+            can_commit: false,
+        };
+        let send_predicates = || {
+            tx1.send(Command::Clear).unwrap();
+            ["a", "b", "c"].into_iter().for_each(|text| {
+                tx1.send(Command::Predicate(predicate(text))).unwrap();
+            });
+            tx1.send(Command::Update).unwrap();
+        };
+        send_predicates();
+        tx1.send(Command::SelectNextPredicate).unwrap();
+        tx1.send(Command::SelectNextPredicate).unwrap();
+        tx1.send(Command::SelectedPredicate).unwrap();
+        assert_eq!(rx2.recv().unwrap(), Command::Predicate(predicate("c")));
+        // The predicates are ranked once, when the list is replaced.
+        tx1.send(Command::Update).unwrap();
+        tx1.send(Command::SelectedPredicate).unwrap();
+        assert_eq!(rx2.recv().unwrap(), Command::Predicate(predicate("c")));
+        send_predicates();
+        tx1.send(Command::SelectedPredicate).unwrap();
+        assert_eq!(rx2.recv().unwrap(), Command::Predicate(predicate("c")));
+        tx1.send(Command::SelectNextPredicate).unwrap();
+        tx1.send(Command::SelectedPredicate).unwrap();
+        assert_eq!(rx2.recv().unwrap(), Command::Predicate(predicate("a")));
+        assert!(ranking_path.exists());
+        fs::remove_file(&ranking_path).unwrap();
+
         // Test the idle state.
         tx1.send(Command::State(true)).unwrap();
         tx1.send(Command::State(false)).unwrap();
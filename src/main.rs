@@ -1,44 +1,405 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use afrim::{run, Config as AfrimConfig};
-use afrim_wish::{Config as WishConfig, Wish};
-use clap::Parser;
+use afrim_wish::{
+    check, check_layer, Config as WishConfig, Diagnostic, Frequencies, Severity, Theme,
+    UserDictionary, Wish,
+};
+use clap::{Parser, ValueEnum};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 /// Afrim Wish CLI.
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Path to the configuration file.
-    config_file: std::path::PathBuf,
+    ///
+    /// It overrides the system, the user and the portable configuration files.
+    config_file: Option<PathBuf>,
 
     /// Only verify if the configuration file is valid.
+    ///
+    /// The problems found are reported on the standard error,
+    /// without starting the graphical interface.
     #[arg(long, action)]
     check: bool,
+
+    /// In check mode, also check the system, the user and the portable configuration files.
+    ///
+    /// They are always checked when no configuration file is given.
+    #[arg(long, action, requires = "check")]
+    with_layers: bool,
+
+    /// Print each value of the configuration with the file it comes from.
+    #[arg(long, action)]
+    show_origins: bool,
+
+    /// Print the configuration in use, with the default values filled.
+    #[arg(long, action)]
+    dump_config: bool,
+
+    /// Print the json schema of the configuration.
+    #[arg(long, action)]
+    print_schema: bool,
+
+    /// Print the names of the built-in themes.
+    #[arg(long, action)]
+    list_presets: bool,
+
+    /// Add a word to the personal dictionary.
+    #[arg(long, num_args = 2, value_names = ["CODE", "TEXT"])]
+    add_word: Option<Vec<String>>,
+
+    /// Remove a code and its words from the personal dictionary.
+    #[arg(long, value_name = "CODE")]
+    remove_word: Option<String>,
+
+    /// Forget the commits counted to rank the predicates.
+    #[arg(long, action)]
+    reset_ranking: bool,
+
+    /// Format of the problems reported in check mode, of the origins, of the
+    /// dumped configuration and of the presets.
+    ///
+    /// The configuration is dumped in toml with the text format.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+impl Args {
+    /// Returns whether only the command line is used, without the graphical interface.
+    fn is_command_line(&self) -> bool {
+        self.check
+            || self.show_origins
+            || self.dump_config
+            || self.print_schema
+            || self.list_presets
+            || self.add_word.is_some()
+            || self.remove_word.is_some()
+            || self.reset_ranking
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// Reports the problems of the configuration files.
+///
+/// Note that Tk is never started in this mode.
+fn check_config(layers: &[PathBuf], format: Format) -> ExitCode {
+    let Some(afrim_file) = WishConfig::afrim_layer(layers) else {
+        eprintln!("No configuration file found");
+
+        return ExitCode::from(2);
+    };
+    let mut reports = vec![];
+
+    for filepath in layers {
+        // A single file should be complete.
+        let diagnostics = if layers.len() > 1 {
+            check_layer(filepath)
+        } else {
+            check(filepath)
+        };
+
+        match diagnostics {
+            Ok(diagnostics) => reports.push((filepath.as_path(), diagnostics)),
+            Err(err) => {
+                eprintln!("{err:?}");
+
+                return ExitCode::from(2);
+            }
+        }
+    }
+    let has_error = |reports: &[(&Path, Vec<Diagnostic>)]| {
+        reports
+            .iter()
+            .flat_map(|(_, diagnostics)| diagnostics)
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    };
+    let unlocated = |err: anyhow::Error| Diagnostic {
+        severity: Severity::Error,
+        line: 0,
+        column: 0,
+        message: format!("{err:#}"),
+        suggestion: None,
+    };
+
+    // The problems of the merged configuration and those found by afrim are not located.
+    if !has_error(&reports) {
+        if let Err(err) = WishConfig::from_layers(layers) {
+            reports.last_mut().unwrap().1.push(unlocated(err));
+        }
+        if let Err(err) = AfrimConfig::from_file(afrim_file) {
+            reports
+                .iter_mut()
+                .find(|(filepath, _)| *filepath == afrim_file)
+                .unwrap()
+                .1
+                .push(unlocated(err));
+        }
+    }
+
+    reports.iter().for_each(|(filepath, diagnostics)| {
+        let filepath = filepath.display();
+
+        match format {
+            Format::Text => diagnostics.iter().for_each(|diagnostic| {
+                if diagnostic.line == 0 {
+                    eprintln!("{filepath}: {diagnostic}");
+                } else {
+                    eprintln!("{filepath}:{diagnostic}");
+                }
+            }),
+            Format::Json => eprintln!(
+                "{}",
+                serde_json::json!({
+                    "file": filepath.to_string(),
+                    "diagnostics": diagnostics,
+                })
+            ),
+        }
+    });
+
+    if has_error(&reports) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Prints each value of the configuration with the file it comes from.
+fn show_origins(layers: &[PathBuf], format: Format) -> ExitCode {
+    let origins = match WishConfig::origins(layers) {
+        Ok(origins) => origins,
+        Err(err) => {
+            eprintln!("{err:?}");
+
+            return ExitCode::from(2);
+        }
+    };
+    // Not empty since the origins are found.
+    let afrim_file = WishConfig::afrim_layer(layers).unwrap().display();
+
+    match format {
+        Format::Text => {
+            println!("# The afrim sections come from {afrim_file}");
+            origins.iter().for_each(|(key, value, filepath)| {
+                println!("{key} = {value}  # {}", filepath.display());
+            });
+        }
+        Format::Json => println!(
+            "{}",
+            serde_json::json!({
+                "afrim": afrim_file.to_string(),
+                "values": origins
+                    .iter()
+                    .map(|(key, value, filepath)| serde_json::json!({
+                        "key": key,
+                        "value": value,
+                        "file": filepath.display().to_string(),
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        ),
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Prints the configuration in use, with the default values filled.
+fn dump_config(layers: &[PathBuf], format: Format) -> ExitCode {
+    let config = match WishConfig::from_layers(layers) {
+        Ok(config) => config.resolved(),
+        Err(err) => {
+            eprintln!("{err:?}");
+
+            return ExitCode::from(2);
+        }
+    };
+    let content = match format {
+        Format::Text => toml::to_string(&config).map_err(anyhow::Error::from),
+        Format::Json => serde_json::to_string_pretty(&config).map_err(anyhow::Error::from),
+    };
+
+    match content {
+        Ok(content) => {
+            println!("{content}");
+
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err:?}");
+
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn main() {
+/// Adds or removes the words of the personal dictionary.
+fn edit_dictionary(layers: &[PathBuf], args: &Args) -> ExitCode {
+    let filepath = match WishConfig::from_layers(layers) {
+        Ok(config) => config.dictionary.unwrap_or_default().path(),
+        Err(err) => {
+            eprintln!("{err:?}");
+
+            return ExitCode::from(2);
+        }
+    };
+    let Some(filepath) = filepath else {
+        eprintln!("Couldn't find the data directory");
+
+        return ExitCode::from(2);
+    };
+    let mut dictionary = match UserDictionary::load(&filepath) {
+        Ok(dictionary) => dictionary,
+        Err(err) => {
+            eprintln!("{err:?}");
+
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(word) = &args.add_word {
+        dictionary.add(&word[0], &word[1]);
+    }
+    if let Some(code) = &args.remove_word {
+        if !dictionary.remove(code) {
+            eprintln!("The code `{code}` is not in the dictionary");
+
+            return ExitCode::FAILURE;
+        }
+    }
+
+    match dictionary.save(&filepath) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err:?}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Forgets the commits counted to rank the predicates.
+fn reset_ranking(layers: &[PathBuf]) -> ExitCode {
+    let filepath = match WishConfig::from_layers(layers) {
+        Ok(config) => config.ranking.unwrap_or_default().path(),
+        Err(err) => {
+            eprintln!("{err:?}");
+
+            return ExitCode::from(2);
+        }
+    };
+    let Some(filepath) = filepath else {
+        eprintln!("Couldn't find the data directory");
+
+        return ExitCode::from(2);
+    };
+
+    match Frequencies::reset(&filepath) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err:?}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints on the console of the parent process, if any.
+///
+/// Note that a release build has no console of its own on windows.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Nothing to do when the parent has no console.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn main() -> ExitCode {
     let args = Args::parse();
+    if args.is_command_line() {
+        attach_console();
+    }
+
+    if args.print_schema {
+        let schema = schemars::schema_for!(WishConfig);
+        println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+
+        return ExitCode::SUCCESS;
+    }
+
+    if args.list_presets {
+        match args.format {
+            Format::Text => Theme::presets()
+                .iter()
+                .for_each(|preset| println!("{preset}")),
+            Format::Json => println!("{}", serde_json::json!(Theme::presets())),
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    let layers = WishConfig::layers(args.config_file.as_deref());
 
-    let wish_conf = WishConfig::from_file(&args.config_file)
+    if args.check {
+        // The configuration file given is checked alone, and should be complete.
+        return match &args.config_file {
+            Some(filepath) if !args.with_layers => {
+                check_config(&[filepath.to_owned()], args.format)
+            }
+            _ => check_config(&layers, args.format),
+        };
+    }
+    if args.show_origins {
+        return show_origins(&layers, args.format);
+    }
+    if args.dump_config {
+        return dump_config(&layers, args.format);
+    }
+    if args.add_word.is_some() || args.remove_word.is_some() {
+        return edit_dictionary(&layers, &args);
+    }
+    if args.reset_ranking {
+        return reset_ranking(&layers);
+    }
+
+    let wish_conf = WishConfig::from_layers(&layers)
         .map_err(|err| {
             Wish::raise_error("Problem parsing config file", &err);
         })
         .unwrap();
 
-    let wish = Wish::from_config(wish_conf);
+    let mut wish = Wish::from_config(wish_conf);
+    wish.watch_config(&layers);
 
-    let afrim_conf = AfrimConfig::from_file(&args.config_file)
+    // Not empty since the wish config is loaded.
+    let afrim_file = WishConfig::afrim_layer(&layers).unwrap();
+    let afrim_conf = AfrimConfig::from_file(afrim_file)
         .map_err(|err| {
             Wish::raise_error("Problem parsing config file", &err);
         })
         .unwrap();
 
-    // End the program if check only.
-    if args.check {
-        Wish::kill();
-    }
-
     if let Err(err) = run(afrim_conf, wish) {
         Wish::raise_error("Application error", &err);
     }
+
+    ExitCode::SUCCESS
 }
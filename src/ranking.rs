@@ -0,0 +1,108 @@
+use super::data;
+use afrim::frontend::Predicate;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::{fs, path::Path};
+
+/// Number of commits of each text, by code.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct Frequencies {
+    counts: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl Frequencies {
+    /// Loads the frequencies of the previous sessions.
+    pub fn load(filepath: &Path) -> Result<Self> {
+        data::load(filepath, "ranking")
+    }
+
+    pub fn save(&self, filepath: &Path) -> Result<()> {
+        data::save(self, filepath, "ranking")
+    }
+
+    /// Forgets the frequencies of the previous sessions.
+    pub fn reset(filepath: &Path) -> Result<()> {
+        if filepath.exists() {
+            fs::remove_file(filepath)
+                .with_context(|| format!("Couldn't remove file {filepath:?}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the complete code of a predicate.
+    fn code(predicate: &Predicate) -> String {
+        format!("{}{}", predicate.code, predicate.remaining_code)
+    }
+
+    /// Counts a commit of the predicate.
+    pub fn record(&mut self, predicate: &Predicate) {
+        *self
+            .counts
+            .entry(Self::code(predicate))
+            .or_default()
+            .entry(predicate.texts[0].to_owned())
+            .or_default() += 1;
+    }
+
+    fn count(&self, predicate: &Predicate) -> u64 {
+        self.counts
+            .get(&Self::code(predicate))
+            .and_then(|texts| texts.get(&predicate.texts[0]))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sorts the predicates from the most committed.
+    ///
+    /// Note that the predicates committed as often keep their order.
+    pub fn sort(&self, predicates: &mut [Predicate]) {
+        predicates.sort_by_key(|predicate| Reverse(self.count(predicate)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Frequencies;
+    use afrim::frontend::Predicate;
+
+    #[test]
+    fn test_frequencies() {
+        let predicate = |code: &str, remaining_code: &str, text: &str| Predicate {
+            code: code.to_owned(),
+            remaining_code: remaining_code.to_owned(),
+            texts: vec![text.to_owned()],
+            can_commit: false,
+        };
+        let mut predicates = vec![
+            predicate("a", "f", "ɑ"),
+            predicate("a", "ff", "ɑɑ"),
+            predicate("a", "f", "α"),
+            predicate("a", "", "a"),
+        ];
+        let mut frequencies = Frequencies::default();
+
+        frequencies.sort(&mut predicates);
+        assert_eq!(predicates[0].texts[0], "ɑ");
+
+        // The complete code is used, whatever the input.
+        frequencies.record(&predicate("af", "", "α"));
+        frequencies.record(&predicate("", "af", "α"));
+        frequencies.record(&predicate("a", "", "a"));
+        frequencies.sort(&mut predicates);
+        let texts: Vec<_> = predicates
+            .iter()
+            .map(|predicate| predicate.texts[0].as_str())
+            .collect();
+        assert_eq!(texts, vec!["α", "a", "ɑ", "ɑɑ"]);
+
+        let content = toml::to_string(&frequencies).unwrap();
+        assert_eq!(
+            toml::from_str::<Frequencies>(&content).unwrap(),
+            frequencies
+        );
+    }
+}
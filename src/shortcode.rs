@@ -0,0 +1,64 @@
+use super::dictionary;
+use afrim::frontend::Predicate;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Returns the emojis and symbols bundled, by shortcode.
+fn shortcodes() -> &'static BTreeMap<String, String> {
+    static SHORTCODES: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+
+    SHORTCODES.get_or_init(|| toml::from_str(include_str!("shortcodes.toml")).unwrap())
+}
+
+/// Returns the predicates of the shortcodes starting with the end of the input,
+/// eg. `:smi` for `:smile`.
+pub fn predicates(input: &str) -> Vec<Predicate> {
+    let Some(start) = input.rfind(':') else {
+        return vec![];
+    };
+    let code = &input[start..];
+    let typed = &code[1..];
+
+    if typed.is_empty()
+        || !typed
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ['_', '+', '-'].contains(&c))
+    {
+        return vec![];
+    }
+
+    shortcodes()
+        .range(typed.to_owned()..)
+        .take_while(|(shortcode, _)| shortcode.starts_with(typed))
+        .map(|(shortcode, text)| dictionary::predicate(code, &shortcode[typed.len()..], text))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{predicates, shortcodes};
+
+    #[test]
+    fn test_shortcodes() {
+        assert!(shortcodes().len() > 100);
+
+        let predicates_of = |input| {
+            predicates(input)
+                .into_iter()
+                .map(|predicate| predicate.texts[0].to_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(predicates_of(":smiley"), vec!["😃"]);
+        assert_eq!(predicates_of(":smil"), vec!["😄", "😃"]);
+        assert_eq!(predicates_of("text:+1"), vec!["👍"]);
+        assert_eq!(predicates_of("na:nai"), vec!["₦"]);
+        assert_eq!(predicates_of(":"), Vec::<String>::new());
+        assert_eq!(predicates_of(":smile:"), Vec::<String>::new());
+        assert_eq!(predicates_of(":a b"), Vec::<String>::new());
+        assert_eq!(predicates_of("smile"), Vec::<String>::new());
+
+        let predicate = &predicates(":eur")[0];
+        assert_eq!(predicate.code, ":eur");
+        assert_eq!(predicate.remaining_code, "o");
+    }
+}
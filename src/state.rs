@@ -0,0 +1,27 @@
+use super::data;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// State of the application, kept between two sessions.
+#[derive(Clone, Deserialize, Serialize, Debug, Default)]
+pub struct State {
+    pub tooltip_position: Option<(u64, u64)>,
+}
+
+impl State {
+    /// Loads the state of the previous session.
+    ///
+    /// Note that an empty state is returned if the state file is unavailable.
+    pub fn load() -> Self {
+        data::default_path("state.toml")
+            .and_then(|filepath| data::load(&filepath, "state").ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let filepath =
+            data::default_path("state.toml").context("Couldn't find the data directory")?;
+
+        data::save(self, &filepath, "state")
+    }
+}
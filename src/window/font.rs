@@ -0,0 +1,116 @@
+use super::config::{Slant, ThemeFont};
+use std::sync::OnceLock;
+
+/// Returns the families of the fonts known by Tk.
+pub fn installed_families() -> &'static [String] {
+    static FAMILIES: OnceLock<Vec<String>> = OnceLock::new();
+
+    FAMILIES.get_or_init(|| {
+        afrish::ask_wish("puts [join [font families] \"\\t\"] ; flush stdout")
+            .trim()
+            .split('\t')
+            .map(str::to_owned)
+            .collect()
+    })
+}
+
+/// Returns the first family of the font which is installed.
+///
+/// Note that Tk compares the families without case.
+pub fn family(font: &ThemeFont, families: &[String]) -> Option<String> {
+    font.families().into_iter().find(|family| {
+        families
+            .iter()
+            .any(|installed| installed.eq_ignore_ascii_case(family))
+    })
+}
+
+/// Returns the description of the font understood by Tk.
+pub fn description(font: &ThemeFont, family: &str) -> String {
+    let mut description = format!("{{{family}}} {} {}", font.size, font.weight);
+
+    if font.slant.unwrap_or_default() == Slant::Italic {
+        description.push_str(" italic");
+    }
+    if font.underline.unwrap_or_default() {
+        description.push_str(" underline");
+    }
+    if font.overstrike.unwrap_or_default() {
+        description.push_str(" overstrike");
+    }
+
+    description
+}
+
+/// Configures the font of a style, with its first family installed.
+///
+/// Note that Tk picks a font when no family is installed.
+pub fn configure(style: &str, font: &ThemeFont) {
+    let family = family(font, installed_families()).unwrap_or_else(|| font.family.to_owned());
+
+    afrish::tell_wish(&format!(
+        "ttk::style configure {style} -font {{{}}}",
+        description(font, &family)
+    ));
+}
+
+/// Returns the warnings about the fonts which are not installed.
+pub fn warnings(fonts: &[ThemeFont]) -> Vec<String> {
+    let mut warnings = vec![];
+
+    fonts.iter().for_each(|font| {
+        let warning = match family(font, installed_families()) {
+            Some(family) if family == font.family => return,
+            Some(family) => format!(
+                "The font `{}` is not installed, `{family}` is used instead.",
+                font.family
+            ),
+            None => format!(
+                "The font `{}` and its fallbacks are not installed, Tk picks a font instead.",
+                font.family
+            ),
+        };
+
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    });
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{description, family};
+    use crate::config::{Slant, ThemeFont};
+
+    #[test]
+    fn test_font() {
+        let mut font = ThemeFont {
+            family: "Charis-SIL".to_owned(),
+            fallbacks: Some(vec!["Gentium Plus".to_owned(), "DejaVu Sans".to_owned()]),
+            size: 10,
+            weight: "bold".to_owned(),
+            slant: None,
+            underline: None,
+            overstrike: None,
+        };
+        let families = ["dejavu sans".to_owned(), "Gentium Plus".to_owned()];
+
+        assert_eq!(family(&font, &families), Some("Gentium Plus".to_owned()));
+        assert_eq!(
+            family(&font, &families[..1]),
+            Some("DejaVu Sans".to_owned())
+        );
+        assert_eq!(family(&font, &[]), None);
+
+        assert_eq!(description(&font, "Gentium Plus"), "{Gentium Plus} 10 bold");
+        font.slant = Some(Slant::Italic);
+        font.underline = Some(true);
+        font.overstrike = Some(false);
+        assert_eq!(
+            description(&font, "Gentium Plus"),
+            "{Gentium Plus} 10 bold italic underline"
+        );
+    }
+}
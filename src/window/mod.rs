@@ -1,4 +1,12 @@
+pub mod font;
+pub mod scaling;
+pub mod script;
+pub mod template;
 pub mod toolkit;
 pub mod tooltip;
 
 use super::config;
+use super::dictionary;
+use super::ranking;
+use super::shortcode;
+use super::state;
@@ -0,0 +1,55 @@
+/// Density of the screen where the interface is displayed as designed.
+const REFERENCE_DPI: f64 = 96.0;
+/// Height of the screen where the interface is displayed as designed.
+const REFERENCE_HEIGHT: f64 = 1080.0;
+
+/// Returns the density of the screen reported by Tk, in dots per inch.
+///
+/// Note that this value changes once the scaling is applied.
+pub fn screen_dpi() -> f64 {
+    afrish::ask_wish("puts [winfo fpixels . 1i] ; flush stdout")
+        .trim()
+        .parse()
+        .unwrap_or(REFERENCE_DPI)
+}
+
+/// Computes the scaling of the interface for a screen.
+///
+/// Tk often reports the reference density whatever the screen,
+/// so the size of the screen is used when the density is not higher.
+/// The scaling is rounded to a quarter, to avoid blurry sizes.
+pub fn detect(dpi: f64, screen: (u64, u64)) -> f64 {
+    let scaling = if dpi > REFERENCE_DPI {
+        dpi / REFERENCE_DPI
+    } else {
+        screen.1 as f64 / REFERENCE_HEIGHT
+    };
+
+    (scaling.clamp(0.5, 4.0) * 4.0).round() / 4.0
+}
+
+/// Scales the fonts of the interface.
+///
+/// Note that the widgets should be rebuilt to use the new sizes.
+pub fn apply(scaling: f64) {
+    // Tk expects a number of pixels per point.
+    afrish::tell_wish(&format!("tk scaling {}", scaling * REFERENCE_DPI / 72.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detect;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(96.0, (1920, 1080)), 1.0);
+        // High density reported.
+        assert_eq!(detect(192.0, (3840, 2160)), 2.0);
+        // Reference density reported on a 4K screen.
+        assert_eq!(detect(96.0, (3840, 2160)), 2.0);
+        // Low resolution.
+        assert_eq!(detect(96.0, (1366, 768)), 0.75);
+        assert_eq!(detect(96.0, (640, 480)), 0.5);
+        assert_eq!(detect(144.0, (2560, 1440)), 1.5);
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use unicode_bidi::{bidi_class, BidiClass, BidiInfo, Level};
+use unicode_script::{Script, UnicodeScript};
+
+/// Returns the names of the styles of a text in a script, in the predicates
+/// and in the selected predicate.
+///
+/// Note that the names live as long as the application, like the styles of Tk.
+pub fn style_names(script: &str) -> (&'static str, &'static str) {
+    static NAMES: OnceLock<Mutex<HashMap<String, (&'static str, &'static str)>>> = OnceLock::new();
+
+    *NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry(script.to_owned())
+        .or_insert_with(|| {
+            let name = |section: &str| -> &'static str {
+                Box::leak(format!("{script}.text.{section}.predicates.TLabel").into_boxed_str())
+            };
+
+            (name("body"), name("selected"))
+        })
+}
+
+/// Returns the ISO 15924 code of the script used by most of the characters of a text.
+///
+/// Note that the characters shared by several scripts, like the digits, are ignored.
+pub fn dominant_script(text: &str) -> Option<&'static str> {
+    let mut counts: Vec<(Script, usize)> = vec![];
+
+    text.chars()
+        .map(|c| c.script())
+        .filter(|script| ![Script::Common, Script::Inherited, Script::Unknown].contains(script))
+        .for_each(
+            |script| match counts.iter_mut().find(|(other, _)| *other == script) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((script, 1)),
+            },
+        );
+
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(script, _)| script.short_name())
+}
+
+/// Returns whether a text is written from right to left.
+///
+/// As in the unicode bidirectional algorithm, the first strong character decides.
+pub fn is_rtl(text: &str) -> bool {
+    text.chars()
+        .map(bidi_class)
+        .find(is_strong)
+        .is_some_and(|class| class != BidiClass::L)
+}
+
+/// Returns whether a text has a character with a direction of its own.
+pub fn has_strong_character(text: &str) -> bool {
+    text.chars().map(bidi_class).any(|class| is_strong(&class))
+}
+
+fn is_strong(class: &BidiClass) -> bool {
+    [BidiClass::L, BidiClass::R, BidiClass::AL].contains(class)
+}
+
+/// Returns the characters of a text in the order of display,
+/// in a paragraph written from right to left or not.
+pub fn visual(text: &str, rtl: bool) -> String {
+    let level = if rtl { Level::rtl() } else { Level::ltr() };
+    let info = BidiInfo::new(text, Some(level));
+
+    info.paragraphs
+        .iter()
+        .map(|paragraph| info.reorder_line(paragraph, paragraph.range.to_owned()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dominant_script, has_strong_character, is_rtl, style_names, visual};
+
+    #[test]
+    fn test_dominant_script() {
+        assert_eq!(dominant_script("ɑ̀ŋ"), Some("Latn"));
+        assert_eq!(dominant_script("ߒߞߏ"), Some("Nkoo"));
+        assert_eq!(dominant_script("ሰላም"), Some("Ethi"));
+        assert_eq!(dominant_script("سَلَام"), Some("Arab"));
+        // Mixed scripts.
+        assert_eq!(dominant_script("ߒߞߏ (nko text)"), Some("Latn"));
+        assert_eq!(dominant_script("ߒߞߏ 1"), Some("Nkoo"));
+        // Same count, the first script wins.
+        assert_eq!(dominant_script("aߒ"), Some("Latn"));
+        assert_eq!(dominant_script("123 !"), None);
+
+        // Direction.
+        assert!(is_rtl("ߒߞߏ"));
+        assert!(is_rtl("1. سَلَام"));
+        assert!(!is_rtl("ɑ̀ŋ ߒ"));
+        assert!(!is_rtl("123"));
+        assert!(has_strong_character("1. ߒ"));
+        assert!(!has_strong_character("1. "));
+        assert_eq!(visual("ߒߞߏ", true), "ߏߞߒ");
+        assert_eq!(visual("ab ߒߞ", false), "ab ߞߒ");
+        assert_eq!(visual("ɑ̀ŋ", false), "ɑ̀ŋ");
+        // The neutral characters follow the paragraph.
+        assert_eq!(visual(". ", true), " .");
+        assert_eq!(visual("1. ߒߞߏ", true), "ߏߞߒ .1");
+        assert_eq!(visual("1. ߒߞߏ", false), "1. ߏߞߒ");
+
+        // The names are created once.
+        assert_eq!(
+            style_names("Thaa"),
+            (
+                "Thaa.text.body.predicates.TLabel",
+                "Thaa.text.selected.predicates.TLabel"
+            )
+        );
+        assert!(std::ptr::eq(style_names("Thaa").0, style_names("Thaa").0));
+    }
+}
@@ -0,0 +1,237 @@
+use afrim::frontend::Predicate;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Value of a predicate that can be displayed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Field {
+    Index,
+    Text,
+    Code,
+    RemainingCode,
+    CanCommit,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "index" => Some(Self::Index),
+            "text" => Some(Self::Text),
+            "code" => Some(Self::Code),
+            "remaining_code" => Some(Self::RemainingCode),
+            "can_commit" => Some(Self::CanCommit),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Literal(String),
+    Field(Field),
+    /// Only displayed when all its fields are not empty.
+    Section(Vec<Token>),
+}
+
+/// Template of a predicate line.
+///
+/// A placeholder is written `{name}` and a conditional section `[...]`.
+/// The braces and the brackets are escaped by doubling them.
+/// Unknown placeholders and unbalanced characters are displayed as is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Template {
+    tokens: Vec<Token>,
+    commit_glyph: String,
+}
+
+impl Template {
+    pub fn new(template: &str, commit_glyph: &str) -> Self {
+        Self {
+            tokens: Self::parse(&mut template.chars().peekable(), false),
+            commit_glyph: commit_glyph.to_owned(),
+        }
+    }
+
+    fn parse(chars: &mut Peekable<Chars>, in_section: bool) -> Vec<Token> {
+        let mut tokens = vec![];
+        let mut literal = String::new();
+
+        macro_rules! flush {
+            () => {
+                if !literal.is_empty() {
+                    tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                }
+            };
+        }
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' | '}' | '[' | ']' if chars.peek() == Some(&c) => {
+                    chars.next();
+                    literal.push(c);
+                }
+                '{' => {
+                    let mut name = String::new();
+                    let mut closed = false;
+
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+
+                    match Field::from_name(&name) {
+                        Some(field) if closed => {
+                            flush!();
+                            tokens.push(Token::Field(field));
+                        }
+                        _ => {
+                            literal.push('{');
+                            literal.push_str(&name);
+                            if closed {
+                                literal.push('}');
+                            }
+                        }
+                    }
+                }
+                '[' if !in_section => {
+                    flush!();
+                    tokens.push(Token::Section(Self::parse(chars, true)));
+                }
+                ']' if in_section => break,
+                c => literal.push(c),
+            }
+        }
+        flush!();
+
+        tokens
+    }
+
+    /// Returns the unknown placeholders of a template.
+    pub fn unknown_placeholders(template: &str) -> Vec<String> {
+        let mut chars = template.chars().peekable();
+        let mut placeholders = vec![];
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                }
+                '{' => {
+                    let mut name = String::new();
+
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            if Field::from_name(&name).is_none() {
+                                placeholders.push(name);
+                            }
+                            break;
+                        }
+                        name.push(c);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        placeholders
+    }
+
+    /// Renders the predicate at the given position.
+    ///
+    /// Returns the rendered parts, with the field from which they come.
+    pub fn render(&self, index: usize, predicate: &Predicate) -> Vec<(Option<Field>, String)> {
+        let value = |field: Field| match field {
+            Field::Index => (index + 1).to_string(),
+            Field::Text => predicate.texts[0].to_owned(),
+            Field::Code => predicate.code.to_owned(),
+            Field::RemainingCode => predicate.remaining_code.to_owned(),
+            Field::CanCommit => {
+                if predicate.can_commit {
+                    self.commit_glyph.to_owned()
+                } else {
+                    String::default()
+                }
+            }
+        };
+        let mut parts = vec![];
+
+        Self::render_tokens(&self.tokens, &value, &mut parts);
+
+        parts
+    }
+
+    fn render_tokens(
+        tokens: &[Token],
+        value: &dyn Fn(Field) -> String,
+        parts: &mut Vec<(Option<Field>, String)>,
+    ) {
+        tokens.iter().for_each(|token| match token {
+            Token::Literal(text) => parts.push((None, text.to_owned())),
+            Token::Field(field) => parts.push((Some(*field), value(*field))),
+            Token::Section(tokens) => {
+                let mut section = vec![];
+
+                Self::render_tokens(tokens, value, &mut section);
+
+                if section
+                    .iter()
+                    .all(|(field, text)| field.is_none() || !text.is_empty())
+                {
+                    parts.extend(section);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Template;
+    use afrim::frontend::Predicate;
+
+    fn render_line(template: &Template, index: usize, predicate: &Predicate) -> String {
+        template
+            .render(index, predicate)
+            .into_iter()
+            .map(|(_, text)| text)
+            .collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let mut predicate = Predicate {
+            code: "af".to_owned(),
+            remaining_code: "3".to_owned(),
+            texts: vec!["ɑ".to_owned()],
+            can_commit: false,
+        };
+
+        let template = Template::new("{index}. {text}[ ~{remaining_code}][ {can_commit}]", "✓");
+        assert_eq!(render_line(&template, 0, &predicate), "1. ɑ ~3");
+
+        let template = Template::new("{text} ({code}){can_commit}[ *{can_commit}]", "✓");
+        assert_eq!(render_line(&template, 1, &predicate), "ɑ (af)");
+
+        // Conditional sections.
+        predicate.remaining_code = String::default();
+        predicate.can_commit = true;
+        let template = Template::new("{index}. {text}[ ~{remaining_code}][ {can_commit}]", "⏎");
+        assert_eq!(render_line(&template, 2, &predicate), "3. ɑ ⏎");
+        let template = Template::new("{text}[ *{can_commit}][ ~{remaining_code}]", "✓");
+        assert_eq!(render_line(&template, 0, &predicate), "ɑ *✓");
+
+        // Escaped and invalid characters.
+        let template = Template::new("{{{index}}} [[{text}]] {unknown} ] {index", "✓");
+        assert_eq!(
+            Template::unknown_placeholders("{{{index}}} [[{text}]] {unknown} ] {index"),
+            vec!["unknown".to_owned()]
+        );
+        assert_eq!(
+            render_line(&template, 0, &predicate),
+            "{1} [ɑ] {unknown} ] {index"
+        );
+    }
+}
@@ -1,18 +1,19 @@
-use super::config::Config;
+use super::config::{Config, ThemeFont};
+use super::font;
 use afrish::*;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-// Ratio to easily adjust the dimension of the gui
-const GUI_RATIO: f64 = 0.8;
-
 #[derive(Clone, Default)]
 pub struct ToolKit {
     themes: HashMap<&'static str, Style>,
+    fonts: HashMap<&'static str, ThemeFont>,
     window: Option<afrish::TkTopLevel>,
+    content_widget: Option<afrish::TkFrame>,
     idle_state_widget: Option<afrish::TkButton>,
     new_idle_state: Arc<Mutex<bool>>,
     curr_idle_state: bool,
+    scaling: f64,
     config: Config,
 }
 
@@ -20,94 +21,75 @@ impl ToolKit {
     pub fn new(config: Config) -> Self {
         Self {
             config,
+            scaling: 1.0,
             ..Default::default()
         }
     }
 
+    /// Returns a size in pixels, adapted to the scaling of the interface.
+    fn scale(&self, size: f64) -> u64 {
+        (size * self.scaling).round() as u64
+    }
+
+    /// Sets the scaling of the sizes in pixels.
+    ///
+    /// Note that the toolkit should be rebuilt to apply it.
+    pub fn set_scaling(&mut self, scaling: f64) {
+        self.scaling = scaling;
+    }
+
     fn build_theme(&mut self) {
-        let font_family = "Charis-SIL";
+        let theme = self.config.theme.to_owned().unwrap_or_default().toolkit();
+
+        macro_rules! make_style {
+            ( $key: expr, $name: expr, $theme: expr ) => {
+                let theme = $theme;
+                let style = Style {
+                    name: $name,
+                    background: theme.background,
+                    foreground: theme.foreground,
+                    font_size: theme.font.size,
+                    font_family: theme.font.family.to_owned(),
+                    font_weight: theme.font.weight.to_owned(),
+                };
+                self.themes.insert($key, style);
+                self.fonts.insert($key, theme.font);
+            };
+        }
 
-        let style = Style {
-            name: "toolkit.TFrame",
-            background: "#1e1e1e".to_owned(),
-            ..Default::default()
-        };
-        self.themes.insert("TFrame", style);
-
-        let style = Style {
-            name: "label.toolkit.TLabel",
-            background: "#1e1e1e".to_owned(),
-            foreground: "#ffffff".to_owned(),
-            font_size: (12.0 * GUI_RATIO) as u64,
-            font_family: font_family.to_string(),
-            ..Default::default()
-        };
-        self.themes.insert("TLabel", style);
-
-        let style = Style {
-            name: "button.toolkit.TButton",
-            background: "#ffffff".to_owned(),
-            foreground: "#1e1e1e".to_owned(),
-            font_size: (12.0 * GUI_RATIO) as u64,
-            font_family: font_family.to_string(),
-            ..Default::default()
-        };
-        self.themes.insert("TButton", style);
-
-        let style = Style {
-            name: "idle.toolkit.TButton",
-            background: "#e03131".to_owned(),
-            foreground: "#1e1e1e".to_owned(),
-            font_size: (12.0 * GUI_RATIO) as u64,
-            font_family: font_family.to_string(),
-            font_weight: "bold".to_owned(),
-        };
-        self.themes.insert("TIButton", style);
-
-        let style = Style {
-            name: "running.toolkit.TButton",
-            background: "#1971c2".to_owned(),
-            foreground: "#1e1e1e".to_owned(),
-            font_size: (12.0 * GUI_RATIO) as u64,
-            font_family: font_family.to_string(),
-            font_weight: "bold".to_owned(),
-        };
-        self.themes.insert("TRButton", style);
-
-        let style = Style {
-            name: "toolkit.TNotebook",
-            background: "#1e1e1e".to_owned(),
-            ..Default::default()
-        };
-        self.themes.insert("TNotebook", style);
-
-        let style = Style {
-            name: "toolkit.TNotebook.Tab",
-            background: "#ffffff".to_owned(),
-            foreground: "#1e1e1e".to_owned(),
-            font_size: (12.0 * GUI_RATIO) as u64,
-            font_family: font_family.to_string(),
-            ..Default::default()
-        };
-        self.themes.insert("notebook", style);
+        make_style!("TFrame", "toolkit.TFrame", theme.frame());
+        make_style!("TLabel", "label.toolkit.TLabel", theme.label());
+        make_style!("TButton", "button.toolkit.TButton", theme.button());
+        make_style!("TIButton", "idle.toolkit.TButton", theme.idle());
+        make_style!("TRButton", "running.toolkit.TButton", theme.running());
+        make_style!("TNotebook", "toolkit.TNotebook", theme.frame());
+        make_style!("notebook", "toolkit.TNotebook.Tab", theme.tab());
 
-        self.themes.iter().for_each(|(_, style)| style.update());
+        self.themes.iter().for_each(|(key, style)| {
+            style.update();
+
+            if let Some(font) = self.fonts.get(key) {
+                font::configure(style.name, font);
+            }
+        });
     }
 
     fn build_window(&mut self) {
         let window = self.window.as_ref().unwrap();
         window.title("Afrim Wish");
         window.resizable(false, false);
-        window.background("#1e1e1e");
-        window.geometry(
-            (480.0 * GUI_RATIO) as u64,
-            (250.0 * GUI_RATIO) as u64,
-            -1,
-            -1,
-        );
+        window.background(&self.themes["TFrame"].background);
+        window.geometry(self.scale(384.0), self.scale(200.0), -1, -1);
+
+        // Content
+        //
+        // Rebuilt at each reload of the configuration.
+        let content = afrish::make_frame(window);
+        content.style(&self.themes["TFrame"]);
+        content.pack().fill(PackFill::Both).expand(true).layout();
 
         // Header
-        let frame = afrish::make_frame(window);
+        let frame = afrish::make_frame(&content);
         frame.style(&self.themes["TFrame"]);
         // Header label
         let label = afrish::make_label(&frame);
@@ -128,39 +110,39 @@ impl ToolKit {
         button
             .pack()
             .side(PackSide::Right)
-            .padx((5.0 * GUI_RATIO) as u64)
+            .padx(self.scale(4.0))
             .layout();
         self.idle_state_widget = Some(button);
         // We build the header
         frame
             .pack()
             .fill(PackFill::X)
-            .padx((20.0 * GUI_RATIO) as u64)
-            .pady((15.0 * GUI_RATIO) as u64)
+            .padx(self.scale(16.0))
+            .pady(self.scale(12.0))
             .layout();
 
         // Separator
-        afrish::make_frame(window)
+        afrish::make_frame(&content)
             .pack()
             .fill(PackFill::X)
-            .padx((30.0 * GUI_RATIO) as u64)
+            .padx(self.scale(24.0))
             .layout();
-        let frame = afrish::make_frame(window);
+        let frame = afrish::make_frame(&content);
         frame.style(&self.themes["TFrame"]);
         frame
             .pack()
             .fill(PackFill::X)
-            .pady((10.0 * GUI_RATIO) as u64)
+            .pady(self.scale(8.0))
             .layout();
 
         // Body
-        let notebook = afrish::make_notebook(window);
+        let notebook = afrish::make_notebook(&content);
         notebook.style(&self.themes["TNotebook"]);
 
         // Page builder
         macro_rules! make_page {
             ( $tabname: expr, $($fieldname: expr => $fieldvalue: expr => $see_more: stmt)*) => {
-                let frame = afrish::make_frame(window);
+                let frame = afrish::make_frame(&content);
                 frame.style(&self.themes["TFrame"]);
 
                 $(
@@ -172,7 +154,7 @@ impl ToolKit {
                     label.pack().side(PackSide::Left).layout();
                     let button = afrish::make_button(&subframe);
                     button.text($fieldvalue);
-                    button.width((25.0 * GUI_RATIO) as i64);
+                    button.width(20);
                     button.style(&self.themes["TButton"]);
                     let cmd = {$see_more};
                     button.command(cmd);
@@ -181,7 +163,7 @@ impl ToolKit {
                     subframe
                         .pack()
                         .fill(PackFill::X)
-                        .pady((2.0 * GUI_RATIO) as u64)
+                        .pady(self.scale(1.0))
                         .layout();
                 )*
 
@@ -278,8 +260,10 @@ impl ToolKit {
         notebook
             .pack()
             .fill(PackFill::X)
-            .padx((20.0 * GUI_RATIO) as u64)
+            .padx(self.scale(16.0))
             .layout();
+
+        self.content_widget = Some(content);
     }
 
     pub fn build(&mut self, window: afrish::TkTopLevel) {
@@ -288,6 +272,18 @@ impl ToolKit {
         self.build_window();
     }
 
+    /// Rebuilds the toolkit with a new configuration.
+    pub fn reload(&mut self, config: Config) {
+        self.config = config;
+
+        if let Some(content_widget) = self.content_widget.take() {
+            content_widget.destroy();
+        }
+        self.build_theme();
+        self.build_window();
+        self.set_idle_state(self.curr_idle_state);
+    }
+
     pub fn new_idle_state(&mut self) -> Option<bool> {
         let curr_idle_state = self.curr_idle_state;
         let new_idle_state = *self.new_idle_state.lock().unwrap();
@@ -1,53 +1,275 @@
-use super::config::Theme;
+use super::config::{Anchor, Config, Corner, Direction, HintTheme, Layout, Placement, ThemeFont};
+use super::dictionary::UserDictionary;
+use super::font;
+use super::ranking::Frequencies;
+use super::script;
+use super::shortcode;
+use super::state::State;
+use super::template::{Field, Template};
 use afrim::frontend::Predicate;
 use afrish::*;
+use anyhow::Result;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Delay in milliseconds before hiding the tooltip after a clear, unless updated meanwhile.
+const HIDE_DELAY: u64 = 50;
+
+/// Actions requested by the user through the tooltip.
+///
+/// Note that a predicate is identified by its index and the generation of its list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    PreviousPage,
+    NextPage,
+    Select(usize, u64),
+    Move,
+}
 
 #[derive(Clone, Default)]
 pub struct ToolTip {
     themes: HashMap<&'static str, Style>,
+    fonts: HashMap<&'static str, ThemeFont>,
     window: Option<afrish::TkTopLevel>,
+    header_widget: Option<afrish::TkFrame>,
     cursor_widget: Option<afrish::TkLabel>,
-    predicates_widget: Option<afrish::TkLabel>,
+    page_widget: Option<afrish::TkLabel>,
+    previous_page_widget: Option<afrish::TkLabel>,
+    next_page_widget: Option<afrish::TkLabel>,
+    predicates_widget: Option<afrish::TkFrame>,
+    predicate_widgets: Vec<afrish::TkFrame>,
     predicates: Vec<Predicate>,
+    dictionary: UserDictionary,
+    shortcodes: bool,
+    /// Predicates of the dictionary and of the shortcodes merged into the predicates.
+    merged_predicates: Vec<Predicate>,
+    frequencies: Frequencies,
+    /// Where the frequencies are saved, unless the learning is disabled.
+    frequencies_path: Option<PathBuf>,
+    /// Whether the predicates were ranked since the list was replaced.
+    ranked: bool,
     current_predicate_id: usize,
+    /// Incremented each time the list of predicates is replaced.
+    generation: u64,
     page_size: usize,
     input: String,
     border: f64,
+    anchor: Anchor,
+    offset: Option<u64>,
+    screen: (u64, u64),
+    position: Option<(f64, f64)>,
+    /// Size requested by the window, measured at each update.
+    size: (f64, f64),
+    template: Template,
+    commit_glyph: String,
+    auto_commit: bool,
+    commit_widget: Option<afrish::TkLabel>,
+    auto_hide: bool,
+    show_delay: u64,
+    visible: bool,
+    placement: Placement,
+    dock: Corner,
+    docked_position: Option<(u64, u64)>,
+    layout: Layout,
+    direction: Direction,
+    separator: String,
+    max_width: Option<usize>,
+    max_text_length: Option<usize>,
+    actions: Arc<Mutex<Vec<Action>>>,
 }
 
 impl ToolTip {
-    pub fn new(theme: Theme) -> Self {
+    pub fn new(config: Config) -> Self {
+        let theme = config.theme.unwrap_or_default();
+        let display = config.display.unwrap_or_default();
+        let core = config.core.unwrap_or_default();
         let mut themes = HashMap::new();
+        let mut fonts = HashMap::new();
+        let selected = theme.selected();
+
+        let style = Style {
+            name: "header.predicates.TFrame",
+            background: theme.header.background.to_owned(),
+            ..Default::default()
+        };
+        themes.insert("PHFrame", style);
 
         let style = Style {
             name: "header.predicates.TLabel",
-            background: theme.header.background,
-            foreground: theme.header.foreground,
+            background: theme.header.background.to_owned(),
+            foreground: theme.header.foreground.to_owned(),
             font_size: theme.header.font.size,
-            font_family: theme.header.font.family,
-            font_weight: theme.header.font.weight,
+            font_family: theme.header.font.family.to_owned(),
+            font_weight: theme.header.font.weight.to_owned(),
         };
         themes.insert("PHLabel", style);
+        fonts.insert("PHLabel", theme.header.font.to_owned());
+
+        let style = Style {
+            name: "selected.predicates.TFrame",
+            background: selected.background.to_owned(),
+            ..Default::default()
+        };
+        themes.insert("PSFrame", style);
+
+        let style = Style {
+            name: "selected.predicates.TLabel",
+            background: selected.background.to_owned(),
+            foreground: selected.foreground.to_owned(),
+            font_size: selected.font.size,
+            font_family: selected.font.family.to_owned(),
+            font_weight: selected.font.weight.to_owned(),
+        };
+        themes.insert("PSLabel", style);
+        fonts.insert("PSLabel", selected.font.to_owned());
+
+        let style = Style {
+            name: "body.predicates.TFrame",
+            background: theme.body.background.to_owned(),
+            ..Default::default()
+        };
+        themes.insert("PBFrame", style);
 
         let style = Style {
             name: "body.predicates.TLabel",
-            background: theme.body.background,
-            foreground: theme.body.foreground,
+            background: theme.body.background.to_owned(),
+            foreground: theme.body.foreground.to_owned(),
             font_size: theme.body.font.size,
             font_family: theme.body.font.family.to_owned(),
             font_weight: theme.body.font.weight.to_owned(),
         };
         themes.insert("PBLabel", style);
+        fonts.insert("PBLabel", theme.body.font.to_owned());
+
+        // Parts of a predicate
+        //
+        // Note that a selected predicate keeps the foreground of the selection.
+        macro_rules! make_hint {
+            ( $key: expr, $name: expr, $hint: expr, $section: expr, $selected: expr ) => {
+                let hint = $hint;
+                let font = hint.font.unwrap_or_else(|| $section.font.to_owned());
+                let foreground = if $selected { None } else { hint.foreground };
+                let style = Style {
+                    name: $name,
+                    background: $section.background.to_owned(),
+                    foreground: foreground.unwrap_or($section.foreground.to_owned()),
+                    font_size: font.size,
+                    font_family: font.family.to_owned(),
+                    font_weight: font.weight.to_owned(),
+                };
+                themes.insert($key, style);
+                fonts.insert($key, font);
+            };
+        }
+        make_hint!(
+            "PBCode",
+            "code.body.predicates.TLabel",
+            theme.code(),
+            theme.body,
+            false
+        );
+        make_hint!(
+            "PSCode",
+            "code.selected.predicates.TLabel",
+            theme.code(),
+            selected,
+            true
+        );
+        make_hint!(
+            "PBRemainingCode",
+            "remaining_code.body.predicates.TLabel",
+            theme.remaining_code(),
+            theme.body,
+            false
+        );
+        make_hint!(
+            "PSRemainingCode",
+            "remaining_code.selected.predicates.TLabel",
+            theme.remaining_code(),
+            selected,
+            true
+        );
+        make_hint!(
+            "PBText",
+            "text.body.predicates.TLabel",
+            theme.text(),
+            theme.body,
+            false
+        );
+        make_hint!(
+            "PSText",
+            "text.selected.predicates.TLabel",
+            theme.text(),
+            selected,
+            true
+        );
+        // Text of the predicates, by script.
+        //
+        // Note that the codes unknown by unicode are never detected.
+        for script in theme.scripts.iter().flat_map(|scripts| scripts.keys()) {
+            let (body_name, selected_name) = script::style_names(script);
+
+            if let Some(font) = theme.script_font(script, &theme.body) {
+                let hint = HintTheme {
+                    font: Some(font),
+                    ..theme.text()
+                };
+                make_hint!(body_name, body_name, hint, theme.body, false);
+            }
+            if let Some(font) = theme.script_font(script, &selected) {
+                let hint = HintTheme {
+                    font: Some(font),
+                    ..theme.text()
+                };
+                make_hint!(selected_name, selected_name, hint, selected, true);
+            }
+        }
+        make_hint!(
+            "PBCanCommit",
+            "can_commit.body.predicates.TLabel",
+            theme.can_commit(),
+            theme.body,
+            false
+        );
+        make_hint!(
+            "PSCanCommit",
+            "can_commit.selected.predicates.TLabel",
+            theme.can_commit(),
+            selected,
+            true
+        );
 
         Self {
             themes,
+            fonts,
+            anchor: theme.anchor(),
+            offset: theme.offset,
+            template: Template::new(&display.template(), &display.commit_glyph()),
+            commit_glyph: display.commit_glyph(),
+            auto_commit: core.auto_commit,
+            auto_hide: display.auto_hide(),
+            show_delay: display.show_delay(),
+            placement: display.placement(),
+            dock: display.dock(),
+            layout: display.layout(),
+            direction: display.direction(),
+            separator: display.separator(),
+            max_width: display.max_width,
+            max_text_length: display.max_text_length,
+            shortcodes: config.shortcodes.unwrap_or_default().enabled(),
             ..Default::default()
         }
     }
 
     fn build_theme(&self) {
-        self.themes.iter().for_each(|(_, style)| style.update());
+        self.themes.iter().for_each(|(key, style)| {
+            style.update();
+
+            if let Some(font) = self.fonts.get(key) {
+                font::configure(style.name, font);
+            }
+        });
     }
 
     fn build_window(&mut self) {
@@ -57,18 +279,95 @@ impl ToolTip {
         window.withdraw();
         window.border(false);
         window.topmost(true);
-        window.deiconify();
+
+        // The tooltip is shown at the first input when hidden automatically.
+        self.visible = !self.auto_hide;
+        if self.visible {
+            window.deiconify();
+        }
+
+        // Header
+        let header_widget = afrish::make_frame(window);
+        header_widget.style(&self.themes["PHFrame"]);
+        header_widget.pack().fill(PackFill::X).layout();
+
+        // Auto commit indicator
+        let commit_widget = afrish::make_label(&header_widget);
+        commit_widget.style(&self.themes["PHLabel"]);
+        commit_widget.pack().side(PackSide::Right).layout();
+        self.commit_widget = Some(commit_widget);
+
+        // Page navigation
+        //
+        // Note that the packing order matters, since they are packed from the right.
+        macro_rules! make_nav {
+            ( $action: expr ) => {{
+                let widget = afrish::make_label(&header_widget);
+                widget.style(&self.themes["PHLabel"]);
+                let actions = Arc::clone(&self.actions);
+                widget.bind("<Button-1>", move |_| {
+                    actions.lock().unwrap().push($action);
+                });
+                widget.pack().side(PackSide::Right).layout();
+
+                widget
+            }};
+        }
+        self.next_page_widget = Some(make_nav!(Action::NextPage));
+        let page_widget = afrish::make_label(&header_widget);
+        page_widget.style(&self.themes["PHLabel"]);
+        page_widget.pack().side(PackSide::Right).layout();
+        self.page_widget = Some(page_widget);
+        self.previous_page_widget = Some(make_nav!(Action::PreviousPage));
 
         // Cursor
-        let cursor_widget = afrish::make_label(window);
+        let cursor_widget = afrish::make_label(&header_widget);
         cursor_widget.text("Afrim is ready for input!");
         cursor_widget.style(&self.themes["PHLabel"]);
-        cursor_widget.pack().fill(PackFill::X).layout();
+        cursor_widget
+            .pack()
+            .side(PackSide::Left)
+            .fill(PackFill::X)
+            .expand(true)
+            .layout();
         self.cursor_widget = Some(cursor_widget);
 
+        // A docked tooltip can be dragged by its header.
+        //
+        // Note that the dragging is done on the wish side, for a better reactivity.
+        if self.placement == Placement::Docked {
+            self.docked_position = State::load().tooltip_position;
+
+            let cursor_widget = self.cursor_widget.as_ref().unwrap();
+            [header_widget.id(), cursor_widget.id()]
+                .iter()
+                .for_each(|id| {
+                    afrish::tell_wish(&format!(
+                        "bind {id} <ButtonPress-1> {{set tooltip_drag [list \
+                            [expr {{%X - [winfo rootx {window}]}}] \
+                            [expr {{%Y - [winfo rooty {window}]}}]]}}
+                        bind {id} <B1-Motion> {{wm geometry {window} \
+                            +[expr {{%X - [lindex $tooltip_drag 0]}}]+[expr {{%Y - [lindex $tooltip_drag 1]}}]}}",
+                        window = window.id()
+                    ));
+                });
+            let actions = Arc::clone(&self.actions);
+            cursor_widget.bind("<ButtonRelease-1>", move |_| {
+                actions.lock().unwrap().push(Action::Move);
+            });
+            let actions = Arc::clone(&self.actions);
+            header_widget.bind("<ButtonRelease-1>", move |_| {
+                actions.lock().unwrap().push(Action::Move);
+            });
+        }
+
+        self.header_widget = Some(header_widget);
+
         // Predication
-        let predicates_widget = afrish::make_label(window);
-        predicates_widget.style(&self.themes["PBLabel"]);
+        //
+        // Each predicate is displayed in its own row, built at each update.
+        let predicates_widget = afrish::make_frame(window);
+        predicates_widget.style(&self.themes["PBFrame"]);
         predicates_widget.pack().fill(PackFill::X).layout();
         self.predicates_widget = Some(predicates_widget);
     }
@@ -79,13 +378,107 @@ impl ToolTip {
         self.build_window();
     }
 
+    /// Rebuilds the tooltip with a new configuration.
+    ///
+    /// Note that the predicates and the position are kept.
+    pub fn reload(&mut self, config: Config) {
+        let mut tooltip = Self::new(config);
+
+        // We drop the widgets of the previous configuration.
+        [self.header_widget.take(), self.predicates_widget.take()]
+            .into_iter()
+            .flatten()
+            .for_each(|widget| widget.destroy());
+
+        tooltip.predicates = std::mem::take(&mut self.predicates);
+        tooltip.dictionary = std::mem::take(&mut self.dictionary);
+        tooltip.merged_predicates = std::mem::take(&mut self.merged_predicates);
+        tooltip.frequencies = std::mem::take(&mut self.frequencies);
+        tooltip.frequencies_path = self.frequencies_path.take();
+        tooltip.ranked = self.ranked;
+        tooltip.current_predicate_id = self.current_predicate_id;
+        tooltip.generation = self.generation;
+        tooltip.actions = Arc::clone(&self.actions);
+        tooltip.page_size = self.page_size;
+        tooltip.input = std::mem::take(&mut self.input);
+        tooltip.border = self.border;
+        tooltip.screen = self.screen;
+        tooltip.position = self.position;
+        tooltip.build(self.window.take().unwrap());
+
+        *self = tooltip;
+        self.update();
+    }
+
     pub fn update_screen(&mut self, screen: (u64, u64)) {
+        self.screen = screen;
         self.border = f64::sqrt((screen.0 * screen.0 + screen.1 * screen.1) as f64) / 100.0;
     }
 
     pub fn update_position(&mut self, position: (f64, f64)) {
-        let x = (position.0 + self.border) as u64;
-        let y = (position.1 + self.border) as u64;
+        // A docked tooltip doesn't follow the cursor.
+        if self.placement == Placement::Docked {
+            return;
+        }
+
+        self.position = Some(position);
+        self.update_placement();
+    }
+
+    /// Measures the size requested by the tooltip window.
+    ///
+    /// Note that it waits for wish, so it should be done once per update.
+    fn requested_size(&self) -> (f64, f64) {
+        let window = self.window.as_ref().unwrap();
+        let size = afrish::ask_wish(&format!(
+            "update idletasks ; puts \"[winfo reqwidth {0}] [winfo reqheight {0}]\" ; flush stdout",
+            window.id()
+        ));
+        let mut size = size
+            .split_whitespace()
+            .map(|value| value.parse::<f64>().unwrap_or_default());
+
+        (
+            size.next().unwrap_or_default(),
+            size.next().unwrap_or_default(),
+        )
+    }
+
+    /// Returns the position of the tooltip window.
+    fn window_position(&self) -> (u64, u64) {
+        let window = self.window.as_ref().unwrap();
+        let position = afrish::ask_wish(&format!(
+            "puts \"[winfo rootx {0}] [winfo rooty {0}]\" ; flush stdout",
+            window.id()
+        ));
+        let mut position = position
+            .split_whitespace()
+            .map(|value| value.parse::<i64>().unwrap_or_default().max(0) as u64);
+
+        (
+            position.next().unwrap_or_default(),
+            position.next().unwrap_or_default(),
+        )
+    }
+
+    /// Moves the tooltip next to the last known position,
+    /// or at its docked position.
+    fn update_placement(&self) {
+        let offset = self
+            .offset
+            .map(|offset| offset as f64)
+            .unwrap_or(self.border);
+        let screen = (self.screen.0 as f64, self.screen.1 as f64);
+
+        let (x, y) = match (self.placement, self.position, self.docked_position) {
+            (Placement::Cursor, Some(position), _) => {
+                placement(self.anchor, position, self.size, screen, offset)
+            }
+            (Placement::Cursor, None, _) => return,
+            (Placement::Docked, _, Some(position)) => position,
+            (Placement::Docked, _, None) => dock(self.dock, self.size, screen, offset),
+        };
+
         self.window.as_ref().unwrap().position(x, y);
     }
 
@@ -93,6 +486,54 @@ impl ToolTip {
         self.input = text;
     }
 
+    pub fn set_dictionary(&mut self, dictionary: UserDictionary) {
+        self.dictionary = dictionary;
+    }
+
+    /// Merges the words of the dictionary and the shortcodes matching the input
+    /// before the predicates.
+    ///
+    /// Note that the words of the previous input are removed.
+    fn merge_predicates(&mut self) {
+        let mut merged_predicates = self.dictionary.predicates(&self.input);
+
+        if self.shortcodes {
+            merged_predicates.extend(shortcode::predicates(&self.input));
+        }
+
+        if merged_predicates == self.merged_predicates {
+            return;
+        }
+
+        self.predicates
+            .retain(|predicate| !self.merged_predicates.contains(predicate));
+        self.predicates
+            .splice(0..0, merged_predicates.iter().cloned());
+        self.merged_predicates = merged_predicates;
+    }
+
+    /// Sets the frequencies used to rank the predicates.
+    ///
+    /// Note that the commits are only counted with a path to save them.
+    pub fn set_frequencies(&mut self, frequencies: Frequencies, filepath: Option<PathBuf>) {
+        self.frequencies = frequencies;
+        self.frequencies_path = filepath;
+    }
+
+    /// Counts a commit of the predicate, to propose it first next time.
+    ///
+    /// Note that the learning stops at the first failure to save the frequencies.
+    pub fn learn(&mut self, predicate: &Predicate) -> Result<()> {
+        let Some(filepath) = &self.frequencies_path else {
+            return Ok(());
+        };
+
+        self.frequencies.record(predicate);
+        self.frequencies.save(filepath).inspect_err(|_| {
+            self.frequencies_path = None;
+        })
+    }
+
     pub fn set_page_size(&mut self, size: usize) {
         self.page_size = size;
     }
@@ -112,8 +553,58 @@ impl ToolTip {
 
     pub fn clear(&mut self) {
         self.predicates.clear();
+        self.merged_predicates.clear();
         self.current_predicate_id = 0;
+        self.generation += 1;
+        self.ranked = false;
         self.input = String::default();
+
+        // A clear precedes each new list of predicates, the tooltip is only hidden
+        // if no update follows, eg. after a commit.
+        if self.auto_hide && self.visible {
+            self.visible = false;
+            afrish::tell_wish(&format!(
+                "set tooltip_hide [after {HIDE_DELAY} {{\
+                    catch {{after cancel $tooltip_show}}; wm withdraw {}}}]",
+                self.window.as_ref().unwrap().id()
+            ));
+        }
+    }
+
+    /// Hides the tooltip when there is nothing to display, if needed.
+    ///
+    /// Note that the tooltip is shown after a delay, to avoid flickering.
+    fn update_visibility(&mut self) {
+        let visible = !self.auto_hide || !self.input.is_empty() || !self.predicates.is_empty();
+
+        if visible == self.visible {
+            return;
+        }
+        self.visible = visible;
+
+        let id = self.window.as_ref().unwrap().id();
+
+        if !visible {
+            afrish::tell_wish(&format!(
+                "catch {{after cancel $tooltip_show}}; \
+                catch {{after cancel $tooltip_hide}}; wm withdraw {id}"
+            ));
+            return;
+        }
+
+        // The tooltip may still be displayed, or about to be.
+        let show = if self.show_delay > 0 {
+            format!(
+                "set tooltip_show [after {} {{wm deiconify {id}}}]",
+                self.show_delay
+            )
+        } else {
+            format!("wm deiconify {id}")
+        };
+        afrish::tell_wish(&format!(
+            "catch {{after cancel $tooltip_hide}}; \
+            if {{![winfo ismapped {id}] && [catch {{after info $tooltip_show}}]}} {{{show}}}"
+        ));
     }
 
     pub fn select_previous_predicate(&mut self) {
@@ -135,33 +626,438 @@ impl ToolTip {
         self.update();
     }
 
+    pub fn select_previous_page(&mut self) {
+        if self.predicates.is_empty() {
+            return;
+        }
+
+        let (page, page_count) = self.page();
+        self.current_predicate_id = ((page + page_count - 1) % page_count) * self.page_size();
+        self.update();
+    }
+
+    pub fn select_next_page(&mut self) {
+        if self.predicates.is_empty() {
+            return;
+        }
+
+        let (page, page_count) = self.page();
+        self.current_predicate_id = ((page + 1) % page_count) * self.page_size();
+        self.update();
+    }
+
     pub fn get_selected_predicate(&self) -> Option<&Predicate> {
         self.predicates.get(self.current_predicate_id)
     }
 
-    pub fn update(&self) {
-        let page_size = std::cmp::min(self.page_size, self.predicates.len());
-        let texts: Vec<String> = self
+    pub fn select_predicate(&mut self, id: usize) {
+        if id >= self.predicates.len() {
+            return;
+        }
+
+        self.current_predicate_id = id;
+        self.update();
+    }
+
+    /// Returns the queue of the actions requested by the user.
+    #[cfg(test)]
+    pub fn actions(&self) -> Arc<Mutex<Vec<Action>>> {
+        Arc::clone(&self.actions)
+    }
+
+    /// Handles the actions requested by the user since the last call.
+    ///
+    /// Note that the actions on a previous list of predicates are dropped,
+    /// and that the other actions are handled even if the position can't be saved.
+    pub fn handle_actions(&mut self) -> Result<()> {
+        let actions: Vec<Action> = self.actions.lock().unwrap().drain(..).collect();
+        let mut result = Ok(());
+
+        actions.into_iter().for_each(|action| match action {
+            Action::PreviousPage => self.select_previous_page(),
+            Action::NextPage => self.select_next_page(),
+            Action::Select(_, generation) if generation != self.generation => {}
+            Action::Select(id, _) => self.select_predicate(id),
+            Action::Move => {
+                let position = self.window_position();
+                let mut state = State::load();
+                state.tooltip_position = Some(position);
+
+                self.docked_position = Some(position);
+                if let Err(err) = state.save() {
+                    result = Err(err);
+                }
+            }
+        });
+
+        result
+    }
+
+    fn page_size(&self) -> usize {
+        std::cmp::max(self.page_size, 1)
+    }
+
+    /// Returns the current page and the number of pages.
+    fn page(&self) -> (usize, usize) {
+        let page_size = self.page_size();
+        let page_count = self.predicates.len().div_ceil(page_size);
+
+        (self.current_predicate_id / page_size, page_count)
+    }
+
+    /// Returns the style of a part of a predicate.
+    ///
+    /// Note that the text uses the style of its script, if any.
+    fn part_style(&self, field: Option<Field>, selected: bool, script: Option<&str>) -> &Style {
+        let script_style = script
+            .map(script::style_names)
+            .map(|(body_name, selected_name)| if selected { selected_name } else { body_name })
+            .and_then(|name| self.themes.get(name));
+
+        if let (Some(Field::Text), Some(style)) = (field, script_style) {
+            return style;
+        }
+
+        let key = match (field, selected) {
+            (Some(Field::Code), false) => "PBCode",
+            (Some(Field::Code), true) => "PSCode",
+            (Some(Field::RemainingCode), false) => "PBRemainingCode",
+            (Some(Field::RemainingCode), true) => "PSRemainingCode",
+            (Some(Field::Text), false) => "PBText",
+            (Some(Field::Text), true) => "PSText",
+            (Some(Field::CanCommit), false) => "PBCanCommit",
+            (Some(Field::CanCommit), true) => "PSCanCommit",
+            (_, false) => "PBLabel",
+            (_, true) => "PSLabel",
+        };
+
+        &self.themes[key]
+    }
+
+    /// A click selects the predicate.
+    ///
+    /// Note that the selected predicate is still committed at the request of afrim,
+    /// with its usual shortcut.
+    fn bind_predicate(&self, widget: &impl TkWidget, id: usize) {
+        let generation = self.generation;
+        let actions = Arc::clone(&self.actions);
+        widget.bind("<Button-1>", move |_| {
+            actions.lock().unwrap().push(Action::Select(id, generation));
+        });
+    }
+
+    pub fn update(&mut self) {
+        self.merge_predicates();
+
+        // The new predicates are ranked once, to keep the selection while the list is displayed.
+        if !self.ranked {
+            self.frequencies.sort(&mut self.predicates);
+            self.ranked = true;
+        }
+
+        let page_size = self.page_size();
+        let (page, page_count) = self.page();
+        let predicates_widget = self.predicates_widget.as_ref().unwrap();
+
+        // We drop the rows of the previous update.
+        self.predicate_widgets
+            .drain(..)
+            .for_each(|widget| widget.destroy());
+
+        let mut predicate_widgets = vec![];
+        let is_rtl = |predicate: &Predicate| match self.direction {
+            Direction::Auto => script::is_rtl(&predicate.texts[0]),
+            Direction::Ltr => false,
+            Direction::Rtl => true,
+        };
+        let side = |rtl: bool| if rtl { PackSide::Right } else { PackSide::Left };
+        // The lines of the horizontal layout follow the first predicate.
+        let line_rtl = self.predicates.get(page * page_size).map_or(false, is_rtl);
+        // Current line of the horizontal layout, with its width in characters.
+        let mut line: Option<(afrish::TkFrame, usize)> = None;
+
+        for (i, predicate) in self
             .predicates
             .iter()
             .enumerate()
-            .chain(self.predicates.iter().enumerate())
-            .skip(self.current_predicate_id)
+            .skip(page * page_size)
             .take(page_size)
-            .map(|(i, predicate)| {
-                format!(
-                    "{}. {} ~{}",
-                    i + 1,
-                    predicate.texts[0],
-                    predicate.remaining_code
-                )
-            })
-            .collect();
+        {
+            let selected = i == self.current_predicate_id;
+            let script = script::dominant_script(&predicate.texts[0]);
+            let rtl = is_rtl(predicate);
+            let parts: Vec<_> = self
+                .template
+                .render(i, predicate)
+                .into_iter()
+                .filter(|(_, text)| !text.is_empty())
+                .map(|(field, text)| match field {
+                    Some(Field::Text) => (field, ellipsize(&text, self.max_text_length)),
+                    _ => (field, text),
+                })
+                .collect();
+
+            let parent = match self.layout {
+                Layout::Vertical => predicates_widget.to_owned(),
+                Layout::Horizontal => {
+                    let width = parts.iter().map(|(_, text)| text.chars().count()).sum();
+                    let separator_width = self.separator.chars().count();
+                    let max_width = self.max_width.unwrap_or(usize::MAX);
+
+                    match line.as_mut() {
+                        Some((line_widget, line_width))
+                            if *line_width + separator_width + width <= max_width =>
+                        {
+                            let separator_widget = afrish::make_label(&*line_widget);
+                            separator_widget.text(&self.separator);
+                            separator_widget.style(&self.themes["PBLabel"]);
+                            separator_widget.pack().side(side(line_rtl)).layout();
+                            *line_width += separator_width + width;
+
+                            line_widget.to_owned()
+                        }
+                        _ => {
+                            let line_widget = afrish::make_frame(predicates_widget);
+                            line_widget.style(&self.themes["PBFrame"]);
+                            line_widget.pack().fill(PackFill::X).layout();
+                            predicate_widgets.push(line_widget.to_owned());
+                            line = Some((line_widget.to_owned(), width));
+
+                            line_widget
+                        }
+                    }
+                }
+            };
+
+            let widget = afrish::make_frame(&parent);
+            widget.style(&self.themes[if selected { "PSFrame" } else { "PBFrame" }]);
+            self.bind_predicate(&widget, i);
+
+            // The parts are packed in the logical order, from the side where the text starts.
+            parts.into_iter().for_each(|(field, text)| {
+                let part_widget = afrish::make_label(&widget);
+                part_widget.text(&display_text(&text, rtl));
+                part_widget.style(self.part_style(field, selected, script));
+                part_widget.pack().side(side(rtl)).layout();
+                self.bind_predicate(&part_widget, i);
+            });
+
+            match self.layout {
+                Layout::Vertical => {
+                    widget.pack().fill(PackFill::X).layout();
+                    predicate_widgets.push(widget);
+                }
+                Layout::Horizontal => widget.pack().side(side(line_rtl)).layout(),
+            }
+        }
+        self.predicate_widgets = predicate_widgets;
 
         self.cursor_widget.as_ref().unwrap().text(&self.input);
-        self.predicates_widget
+
+        // The next keystroke will commit the selected predicate.
+        let will_commit = self.auto_commit
+            && self
+                .get_selected_predicate()
+                .map_or(false, |predicate| predicate.can_commit);
+        let commit_text = if will_commit {
+            format!(" {} auto commit", self.commit_glyph)
+        } else {
+            String::default()
+        };
+        self.commit_widget.as_ref().unwrap().text(&commit_text);
+
+        // Page indicator
+        let (page_text, previous_text, next_text) = match page_count {
+            0 => (String::default(), "", ""),
+            1 => (format!("{} candidates", self.predicates.len()), "", ""),
+            _ => (
+                format!(
+                    "page {}/{} · {} candidates",
+                    page + 1,
+                    page_count,
+                    self.predicates.len()
+                ),
+                "‹",
+                "›",
+            ),
+        };
+        self.page_widget.as_ref().unwrap().text(&page_text);
+        self.previous_page_widget
             .as_ref()
             .unwrap()
-            .text(&texts.join("\n"));
+            .text(previous_text);
+        self.next_page_widget.as_ref().unwrap().text(next_text);
+
+        // The size of the tooltip may have changed.
+        self.size = self.requested_size();
+        self.update_placement();
+        self.update_visibility();
+    }
+}
+
+/// Computes the position of a window of the given size next to a point.
+///
+/// The window is flipped on the other side of the point when it overflows the screen,
+/// and clamped to the screen as last resort. An empty screen means an unknown screen.
+fn placement(
+    anchor: Anchor,
+    point: (f64, f64),
+    size: (f64, f64),
+    screen: (f64, f64),
+    offset: f64,
+) -> (u64, u64) {
+    let (right, below) = match anchor {
+        Anchor::BelowRight => (true, true),
+        Anchor::BelowLeft => (false, true),
+        Anchor::AboveRight => (true, false),
+        Anchor::AboveLeft => (false, false),
+    };
+
+    let place = |point: f64, size: f64, screen: f64, forward: bool| {
+        let after = point + offset;
+        let before = point - offset - size;
+        let fits_after = screen <= 0.0 || after + size <= screen;
+        let fits_before = before >= 0.0;
+
+        let position = match forward {
+            true if fits_after || !fits_before => after,
+            false if !fits_before && fits_after => after,
+            _ => before,
+        };
+        let position = if screen > 0.0 {
+            position.min(screen - size)
+        } else {
+            position
+        };
+
+        position.max(0.0) as u64
+    };
+
+    (
+        place(point.0, size.0, screen.0, right),
+        place(point.1, size.1, screen.1, below),
+    )
+}
+
+/// Returns a part of a row in the order expected by Tk.
+///
+/// Note that Tk draws the characters in their logical order on X11, and elsewhere
+/// reorders a text without strong characters, like `". "`, from left to right.
+fn display_text(text: &str, rtl: bool) -> String {
+    if cfg!(any(windows, target_os = "macos")) && script::has_strong_character(text) {
+        text.to_owned()
+    } else {
+        script::visual(text, rtl)
+    }
+}
+
+/// Shortens a text longer than the maximum length with an ellipsis.
+fn ellipsize(text: &str, max_length: Option<usize>) -> String {
+    match max_length {
+        Some(max_length) if text.chars().count() > max_length => text
+            .chars()
+            .take(max_length.saturating_sub(1))
+            .chain(std::iter::once('…'))
+            .collect(),
+        _ => text.to_owned(),
+    }
+}
+
+/// Computes the position of a window of the given size in a corner of the screen.
+fn dock(corner: Corner, size: (f64, f64), screen: (f64, f64), margin: f64) -> (u64, u64) {
+    let (x, y) = match corner {
+        Corner::TopLeft => (margin, margin),
+        Corner::TopRight => (screen.0 - size.0 - margin, margin),
+        Corner::BottomLeft => (margin, screen.1 - size.1 - margin),
+        Corner::BottomRight => (screen.0 - size.0 - margin, screen.1 - size.1 - margin),
+    };
+
+    (x.max(0.0) as u64, y.max(0.0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{display_text, dock, ellipsize, placement};
+    use crate::config::{Anchor, Corner};
+
+    #[test]
+    fn test_placement() {
+        let size = (50.0, 20.0);
+        let screen = (1000.0, 800.0);
+
+        // Enough space.
+        assert_eq!(
+            placement(Anchor::BelowRight, (100.0, 100.0), size, screen, 10.0),
+            (110, 110)
+        );
+        assert_eq!(
+            placement(Anchor::AboveLeft, (100.0, 100.0), size, screen, 10.0),
+            (40, 70)
+        );
+
+        // Flipped near the edges.
+        assert_eq!(
+            placement(Anchor::BelowRight, (990.0, 790.0), size, screen, 10.0),
+            (930, 760)
+        );
+        assert_eq!(
+            placement(Anchor::AboveLeft, (5.0, 5.0), size, screen, 10.0),
+            (15, 15)
+        );
+
+        // Clamped to the screen.
+        assert_eq!(
+            placement(
+                Anchor::BelowRight,
+                (100.0, 100.0),
+                (2000.0, 20.0),
+                screen,
+                10.0
+            ),
+            (0, 110)
+        );
+
+        // Unknown screen.
+        assert_eq!(
+            placement(Anchor::BelowLeft, (5.0, 5.0), size, (0.0, 0.0), 10.0),
+            (15, 15)
+        );
+    }
+
+    #[test]
+    fn test_dock() {
+        let size = (50.0, 20.0);
+        let screen = (1000.0, 800.0);
+
+        assert_eq!(dock(Corner::TopLeft, size, screen, 10.0), (10, 10));
+        assert_eq!(dock(Corner::TopRight, size, screen, 10.0), (940, 10));
+        assert_eq!(dock(Corner::BottomLeft, size, screen, 10.0), (10, 770));
+        assert_eq!(dock(Corner::BottomRight, size, screen, 10.0), (940, 770));
+        assert_eq!(dock(Corner::BottomRight, size, (0.0, 0.0), 10.0), (0, 0));
+    }
+
+    #[test]
+    fn test_ellipsize() {
+        assert_eq!(ellipsize("ŋgɔŋ", None), "ŋgɔŋ");
+        assert_eq!(ellipsize("ŋgɔŋ", Some(4)), "ŋgɔŋ");
+        assert_eq!(ellipsize("ŋgɔŋ", Some(3)), "ŋg…");
+        assert_eq!(ellipsize("ŋgɔŋ", Some(0)), "…");
+    }
+
+    #[test]
+    #[cfg(not(any(windows, target_os = "macos")))]
+    fn test_display_text() {
+        // The parts of a row from right to left are packed from the right.
+        let row = |parts: &[&str], rtl| {
+            let mut parts: Vec<_> = parts.iter().map(|part| display_text(part, rtl)).collect();
+            if rtl {
+                parts.reverse();
+            }
+            parts.concat()
+        };
+
+        assert_eq!(row(&["1", ". ", "ߒߞߏ"], true), "ߏߞߒ .1");
+        assert_eq!(row(&["1", ". ", "ߒߞߏ", " ~", "3"], true), "3~ ߏߞߒ .1");
+        assert_eq!(row(&["1", ". ", "ɑ̀ŋ", " ~", "3"], false), "1. ɑ̀ŋ ~3");
     }
 }